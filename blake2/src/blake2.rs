@@ -1,8 +1,8 @@
 macro_rules! blake2_impl {
     (
-        $state:ident, $fix_state:ident, $word:ident, $vec:ident, $bytes:ident,
-        $block_size:ident, $R1:expr, $R2:expr, $R3:expr, $R4:expr, $IV:expr,
-        $vardoc:expr, $doc:expr,
+        $state:ident, $fix_state:ident, $params:ident, $word:ident, $vec:ident,
+        $bytes:ident, $block_size:ident, $R1:expr, $R2:expr, $R3:expr, $R4:expr,
+        $IV:expr, $x86_compress:ident, $x86_many:ident, $vardoc:expr, $doc:expr, $params_doc:expr,
     ) => {
 
         use $crate::as_bytes::AsBytes;
@@ -11,9 +11,10 @@ macro_rules! blake2_impl {
         use digest::{Update, BlockInput, FixedOutputDirty, VariableOutputDirty, Reset};
         use digest::InvalidOutputSize;
         use digest::generic_array::GenericArray;
-        use digest::generic_array::typenum::{U4, Unsigned};
-        use core::{cmp, convert::TryInto, ops::Div};
+        use digest::generic_array::typenum::Unsigned;
+        use core::{cmp, convert::TryInto};
         use crypto_mac::{InvalidKeyLength, Mac, NewMac};
+        use $crate::ParamsError;
 
         type Output = GenericArray<u8, $bytes>;
 
@@ -24,6 +25,7 @@ macro_rules! blake2_impl {
             h: [$vec; 2],
             t: u64,
             n: usize,
+            last_node: bool,
 
             h0: [$vec; 2],
             m0: [$word; 16],
@@ -83,50 +85,28 @@ macro_rules! blake2_impl {
             }
 
             /// Creates a new hashing context with the full set of sequential-mode parameters.
+            ///
+            /// # Panics
+            ///
+            /// If any of the parameters is too long or `output_size` is zero. Use
+            /// the `Blake2bParams` and `Blake2sParams` builders to handle invalid
+            /// parameters without panicking.
             pub fn with_params(key: &[u8], salt: &[u8], persona: &[u8], output_size: usize) -> Self {
-                let kk = key.len();
-                assert!(kk <= $bytes::to_usize());
-                assert!(output_size <= $bytes::to_usize());
-
-                // The number of bytes needed to express two words.
-                let length = $bytes::to_usize()/4;
-                assert!(salt.len() <= length);
-                assert!(persona.len() <= length);
-
-                // Build a parameter block
-                let mut p = [0 as $word; 8];
-                p[0] = 0x0101_0000 ^ ((kk as $word) << 8) ^
-                    (output_size as $word);
-
-                // salt is two words long
-                if salt.len() < length {
-                    let mut padded_salt = GenericArray::<u8, <$bytes as Div<U4>>::Output>::default();
-                    for i in 0..salt.len() {
-                        padded_salt[i] = salt[i];
-                    }
-                    p[4] = $word::from_le_bytes(padded_salt[0 .. length/2].try_into().unwrap());
-                    p[5] = $word::from_le_bytes(padded_salt[length/2 .. padded_salt.len()].try_into().unwrap());
-                } else {
-                    p[4] = $word::from_le_bytes(salt[0 .. salt.len()/2].try_into().unwrap());
-                    p[5] = $word::from_le_bytes(salt[salt.len()/2 .. salt.len()].try_into().unwrap());
-                }
-
-                // persona is also two words long
-                if persona.len() < length {
-                    let mut padded_persona = GenericArray::<u8, <$bytes as Div<U4>>::Output>::default();
-                    for i in 0..persona.len() {
-                        padded_persona[i] = persona[i];
-                    }
-                    p[6] = $word::from_le_bytes(padded_persona[0 .. length/2].try_into().unwrap());
-                    p[7] = $word::from_le_bytes(padded_persona[length/2 .. padded_persona.len()].try_into().unwrap());
-                } else {
-                    p[6] = $word::from_le_bytes(persona[0 .. length/2].try_into().unwrap());
-                    p[7] = $word::from_le_bytes(persona[length/2 .. persona.len()].try_into().unwrap());
-                }
+                $params::new()
+                    .digest_length(output_size)
+                    .key(key)
+                    .salt(salt)
+                    .persona(persona)
+                    .build()
+                    .expect("invalid BLAKE2 parameters")
+            }
 
-                let mut state = Self::with_parameter_block(&p);
+            /// Creates a new hashing context from a parameter block, processing
+            /// `key` as the first block if it is not empty.
+            pub(crate) fn with_parameter_block_keyed(p: &[$word; 8], key: &[u8]) -> Self {
+                let mut state = Self::with_parameter_block(p);
 
-                if kk > 0 {
+                if !key.is_empty() {
                     copy(key, state.m.as_mut_bytes());
                     state.t = 2 * $bytes::to_u64();
                 }
@@ -153,6 +133,7 @@ macro_rules! blake2_impl {
                     h: h0,
                     t: 0,
                     n: nn,
+                    last_node: false,
 
                     t0: 0,
                     m0: [0; 16],
@@ -161,7 +142,7 @@ macro_rules! blake2_impl {
             }
 
             /// Updates the hashing context with more data.
-            fn update(&mut self, data: &[u8]) {
+            pub(crate) fn update(&mut self, data: &[u8]) {
                 let mut rest = data;
 
                 let block = 2 * $bytes::to_usize();
@@ -205,7 +186,7 @@ macro_rules! blake2_impl {
             }
 
 
-            fn finalize_with_flag(&mut self, f1: $word) -> Output {
+            pub(crate) fn finalize_with_flag(&mut self, f1: $word) -> Output {
                 let off = self.t as usize % (2 * $bytes::to_usize());
                 if off != 0 {
                     self.m.as_mut_bytes()[off..].iter_mut().for_each(|b| *b = 0);
@@ -221,43 +202,61 @@ macro_rules! blake2_impl {
             }
 
             fn compress(&mut self, f0: $word, f1: $word) {
-                use $crate::consts::SIGMA;
-
-                let m = &self.m;
-                let h = &mut self.h;
-
-                let t0 = self.t as $word;
-                let t1 = match $bytes::to_u8() {
-                    64 => 0,
-                    32 => (self.t >> 32) as $word,
-                    _  => unreachable!(),
-                };
-
-                let mut v = [
-                    h[0],
-                    h[1],
-                    iv0(),
-                    iv1() ^ $vec::new(t0, t1, f0, f1),
-                ];
+                compress(&mut self.h, &self.m, self.t, f0, f1);
+            }
+        }
 
-                round(&mut v, m, &SIGMA[0]);
-                round(&mut v, m, &SIGMA[1]);
-                round(&mut v, m, &SIGMA[2]);
-                round(&mut v, m, &SIGMA[3]);
-                round(&mut v, m, &SIGMA[4]);
-                round(&mut v, m, &SIGMA[5]);
-                round(&mut v, m, &SIGMA[6]);
-                round(&mut v, m, &SIGMA[7]);
-                round(&mut v, m, &SIGMA[8]);
-                round(&mut v, m, &SIGMA[9]);
-                if $bytes::to_u8() == 64 {
-                    round(&mut v, m, &SIGMA[0]);
-                    round(&mut v, m, &SIGMA[1]);
+        /// Compresses the block `m` into the state `h`, where `t` is the number
+        /// of bytes hashed so far and `f0` and `f1` are the finalization flags.
+        fn compress(h: &mut [$vec; 2], m: &[$word; 16], t: u64, f0: $word, f1: $word) {
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                not(any(feature = "simd", feature = "force-soft")),
+            ))]
+            {
+                if $crate::x86::$x86_compress(h, m, t, f0, f1) {
+                    return;
                 }
+            }
+
+            compress_soft(h, m, t, f0, f1);
+        }
 
-                h[0] = h[0] ^ (v[0] ^ v[2]);
-                h[1] = h[1] ^ (v[1] ^ v[3]);
+        /// Portable implementation of `compress`.
+        pub(crate) fn compress_soft(h: &mut [$vec; 2], m: &[$word; 16], t: u64, f0: $word, f1: $word) {
+            use $crate::consts::SIGMA;
+
+            let t0 = t as $word;
+            let t1 = match $bytes::to_u8() {
+                64 => 0,
+                32 => (t >> 32) as $word,
+                _  => unreachable!(),
+            };
+
+            let mut v = [
+                h[0],
+                h[1],
+                iv0(),
+                iv1() ^ $vec::new(t0, t1, f0, f1),
+            ];
+
+            round(&mut v, m, &SIGMA[0]);
+            round(&mut v, m, &SIGMA[1]);
+            round(&mut v, m, &SIGMA[2]);
+            round(&mut v, m, &SIGMA[3]);
+            round(&mut v, m, &SIGMA[4]);
+            round(&mut v, m, &SIGMA[5]);
+            round(&mut v, m, &SIGMA[6]);
+            round(&mut v, m, &SIGMA[7]);
+            round(&mut v, m, &SIGMA[8]);
+            round(&mut v, m, &SIGMA[9]);
+            if $bytes::to_u8() == 64 {
+                round(&mut v, m, &SIGMA[0]);
+                round(&mut v, m, &SIGMA[1]);
             }
+
+            h[0] = h[0] ^ (v[0] ^ v[2]);
+            h[1] = h[1] ^ (v[1] ^ v[3]);
         }
 
         impl Default for $state {
@@ -288,7 +287,7 @@ macro_rules! blake2_impl {
 
             fn finalize_variable_dirty(&mut self, f: impl FnOnce(&[u8])) {
                 let n = self.n;
-                let res = self.finalize_with_flag(0);
+                let res = self.finalize_with_flag(if self.last_node { !0 } else { 0 });
                 f(&res[..n]);
             }
         }
@@ -304,6 +303,207 @@ macro_rules! blake2_impl {
         opaque_debug::implement!($state);
         digest::impl_write!($state);
 
+        #[derive(Clone, Copy)]
+        #[doc=$params_doc]
+        ///
+        /// All fields of the parameter block can be set, the defaults are those
+        /// of the sequential mode with the maximum digest length. Invalid
+        /// values are reported by `build` instead of panicking.
+        pub struct $params<'a> {
+            digest_length: usize,
+            key: &'a [u8],
+            salt: &'a [u8],
+            persona: &'a [u8],
+            fanout: u8,
+            max_depth: u8,
+            leaf_length: u32,
+            node_offset: u64,
+            node_depth: u8,
+            inner_length: usize,
+            last_node: bool,
+        }
+
+        impl<'a> $params<'a> {
+            /// Creates parameters for the sequential mode.
+            pub fn new() -> Self {
+                $params {
+                    digest_length: $bytes::to_usize(),
+                    key: &[],
+                    salt: &[],
+                    persona: &[],
+                    fanout: 1,
+                    max_depth: 1,
+                    leaf_length: 0,
+                    node_offset: 0,
+                    node_depth: 0,
+                    inner_length: 0,
+                    last_node: false,
+                }
+            }
+
+            /// Sets the digest length in bytes, between 1 and the output size
+            /// of the hash function.
+            pub fn digest_length(&mut self, length: usize) -> &mut Self {
+                self.digest_length = length;
+                self
+            }
+
+            /// Sets the key, at most as long as the output size of the hash
+            /// function.
+            pub fn key(&mut self, key: &'a [u8]) -> &mut Self {
+                self.key = key;
+                self
+            }
+
+            /// Sets the salt, at most a quarter of the output size long.
+            /// Shorter salts are padded with zeros.
+            pub fn salt(&mut self, salt: &'a [u8]) -> &mut Self {
+                self.salt = salt;
+                self
+            }
+
+            /// Sets the personalization, at most a quarter of the output size
+            /// long. Shorter values are padded with zeros.
+            pub fn persona(&mut self, persona: &'a [u8]) -> &mut Self {
+                self.persona = persona;
+                self
+            }
+
+            /// Sets the fanout, 0 means unlimited.
+            pub fn fanout(&mut self, fanout: u8) -> &mut Self {
+                self.fanout = fanout;
+                self
+            }
+
+            /// Sets the maximal depth of the tree, between 1 and 255 which
+            /// means unlimited.
+            pub fn max_depth(&mut self, depth: u8) -> &mut Self {
+                self.max_depth = depth;
+                self
+            }
+
+            /// Sets the maximal byte length of the leaves, 0 means unlimited.
+            pub fn leaf_length(&mut self, length: u32) -> &mut Self {
+                self.leaf_length = length;
+                self
+            }
+
+            /// Sets the offset of the node in its level of the tree. For
+            /// BLAKE2s it must fit into 48 bits.
+            pub fn node_offset(&mut self, offset: u64) -> &mut Self {
+                self.node_offset = offset;
+                self
+            }
+
+            /// Sets the depth of the node in the tree, 0 for the leaves.
+            pub fn node_depth(&mut self, depth: u8) -> &mut Self {
+                self.node_depth = depth;
+                self
+            }
+
+            /// Sets the inner hash length in bytes, at most the output size of
+            /// the hash function.
+            pub fn inner_length(&mut self, length: usize) -> &mut Self {
+                self.inner_length = length;
+                self
+            }
+
+            /// Sets whether the node is the last one of its level, which
+            /// changes the finalization.
+            pub fn last_node(&mut self, last_node: bool) -> &mut Self {
+                self.last_node = last_node;
+                self
+            }
+
+            /// Creates a hashing context with these parameters.
+            pub fn build(&self) -> Result<$state, ParamsError> {
+                let n = $bytes::to_usize();
+                if self.digest_length == 0 || self.digest_length > n {
+                    return Err(ParamsError::DigestLength);
+                }
+                if self.key.len() > n {
+                    return Err(ParamsError::KeyLength);
+                }
+                if self.salt.len() > n / 4 {
+                    return Err(ParamsError::SaltLength);
+                }
+                if self.persona.len() > n / 4 {
+                    return Err(ParamsError::PersonaLength);
+                }
+                if self.max_depth == 0 {
+                    return Err(ParamsError::MaxDepth);
+                }
+                if self.inner_length > n {
+                    return Err(ParamsError::InnerLength);
+                }
+
+                let offset_bits = 8 * (Self::depth_pos() as u32 - 8);
+                if self.node_offset.checked_shr(offset_bits).unwrap_or(0) != 0 {
+                    return Err(ParamsError::NodeOffset);
+                }
+
+                let p = self.param_block();
+                let mut state = $state::with_parameter_block_keyed(&p, self.key);
+                state.last_node = self.last_node;
+                Ok(state)
+            }
+
+            /// Position of the node depth, which follows the node offset.
+            fn depth_pos() -> usize {
+                match $bytes::to_u8() {
+                    64 => 16,
+                    32 => 14,
+                    _ => unreachable!(),
+                }
+            }
+
+            /// Packs the parameters into words without validating them.
+            pub(crate) fn param_block(&self) -> [$word; 8] {
+                let n = $bytes::to_usize();
+                let depth_pos = Self::depth_pos();
+
+                let mut block = GenericArray::<u8, $bytes>::default();
+                block[0] = self.digest_length as u8;
+                block[1] = self.key.len() as u8;
+                block[2] = self.fanout;
+                block[3] = self.max_depth;
+                block[4..8].copy_from_slice(&self.leaf_length.to_le_bytes());
+                block[8..depth_pos].copy_from_slice(&self.node_offset.to_le_bytes()[..depth_pos - 8]);
+                block[depth_pos] = self.node_depth;
+                block[depth_pos + 1] = self.inner_length as u8;
+                block[n / 2..n / 2 + self.salt.len()].copy_from_slice(self.salt);
+                block[3 * n / 4..3 * n / 4 + self.persona.len()].copy_from_slice(self.persona);
+
+                let mut p = [0 as $word; 8];
+                for (word, chunk) in p.iter_mut().zip(block.chunks(n / 8)) {
+                    *word = $word::from_le_bytes(chunk.try_into().unwrap());
+                }
+                p
+            }
+        }
+
+        impl Default for $params<'_> {
+            fn default() -> Self { Self::new() }
+        }
+
+        // the key is omitted
+        impl core::fmt::Debug for $params<'_> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($params))
+                    .field("digest_length", &self.digest_length)
+                    .field("salt", &self.salt)
+                    .field("persona", &self.persona)
+                    .field("fanout", &self.fanout)
+                    .field("max_depth", &self.max_depth)
+                    .field("leaf_length", &self.leaf_length)
+                    .field("node_offset", &self.node_offset)
+                    .field("node_depth", &self.node_depth)
+                    .field("inner_length", &self.inner_length)
+                    .field("last_node", &self.last_node)
+                    .finish()
+            }
+        }
+
 
         #[derive(Clone)]
         #[doc=$doc]
@@ -385,6 +585,8 @@ macro_rules! blake2_impl {
         opaque_debug::implement!($fix_state);
         digest::impl_write!($fix_state);
 
+        hash_many_impl!($fix_state, $state, $params, $word, $vec, $bytes, $x86_many,);
+
         fn copy(src: &[u8], dst: &mut [u8]) {
             assert!(dst.len() >= src.len());
             unsafe {
@@ -4,6 +4,7 @@ use digest::generic_array::typenum::{U128, U64};
 blake2_impl!(
     VarBlake2b,
     Blake2b,
+    Blake2bParams,
     u64,
     u64x4,
     U64,
@@ -13,6 +14,9 @@ blake2_impl!(
     16,
     63,
     BLAKE2B_IV,
+    compress_b,
+    detect_many_b,
     "Blake2b instance with a variable output.",
     "Blake2b instance with a fixed output.",
+    "Builder for the parameter block of `VarBlake2b`.",
 );
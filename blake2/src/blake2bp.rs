@@ -0,0 +1,12 @@
+use crate::blake2b::VarBlake2b;
+use digest::generic_array::typenum::{U128, U4, U64};
+
+blake2p_impl!(
+    Blake2bp,
+    VarBlake2b,
+    u64,
+    U64,
+    U128,
+    U4,
+    "Blake2bp instance, i.e. Blake2b with 4 leaves hashed in parallel.",
+);
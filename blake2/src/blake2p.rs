@@ -0,0 +1,297 @@
+macro_rules! blake2p_impl {
+    (
+        $state:ident, $leaf:ident, $word:ident, $bytes:ident, $block_size:ident,
+        $degree:ident, $doc:expr,
+    ) => {
+
+        use digest::{Update, BlockInput, FixedOutputDirty, Reset};
+        use digest::generic_array::GenericArray;
+        use digest::generic_array::sequence::GenericSequence;
+        use digest::generic_array::typenum::Unsigned;
+        use core::cmp;
+        use crypto_mac::{InvalidKeyLength, Mac, NewMac};
+
+        type Output = GenericArray<u8, $bytes>;
+
+        const BLOCK: usize = <$block_size as Unsigned>::USIZE;
+        const DEGREE: usize = <$degree as Unsigned>::USIZE;
+        /// Length of one stripe, i.e. one block for each leaf.
+        const STRIPE: usize = DEGREE * BLOCK;
+
+        /// Minimum length of input for which the leaves are hashed on the
+        /// rayon thread pool. Shorter inputs to `update` are buffered until
+        /// this length is reached.
+        #[cfg(feature = "rayon")]
+        const PARALLEL_MIN_LEN: usize = 1 << 16;
+
+        /// Minimum length of buffered input for which the leaves are
+        /// finalized on the rayon thread pool.
+        #[cfg(feature = "rayon")]
+        const PARALLEL_FINALIZE_MIN_LEN: usize = 1 << 12;
+
+        #[cfg(all(test, feature = "rayon"))]
+        std::thread_local! {
+            /// Number of times the rayon thread pool was used by this thread.
+            static PARALLEL_CALLS: core::cell::Cell<usize> = core::cell::Cell::new(0);
+        }
+
+        #[derive(Clone)]
+        #[doc=$doc]
+        pub struct $state {
+            leaves: GenericArray<$leaf, $degree>,
+            buf: [u8; STRIPE],
+            buf_len: usize,
+            key_len: usize,
+            /// Whole stripes which have not been fed to the leaves yet.
+            #[cfg(feature = "rayon")]
+            pending: std::vec::Vec<u8>,
+        }
+
+        /// Builds the parameter block of a node of the tree.
+        fn param_block(key_len: usize, node_offset: usize, node_depth: usize) -> [$word; 8] {
+            let mut p = [0 as $word; 8];
+            p[0] = ($bytes::to_usize() as $word) ^ ((key_len as $word) << 8) ^
+                ((DEGREE as $word) << 16) ^ (2 << 24);
+            match $bytes::to_u8() {
+                64 => {
+                    p[1] = node_offset as $word;
+                    p[2] = (node_depth as $word) ^ (($bytes::to_usize() as $word) << 8);
+                }
+                32 => {
+                    p[2] = node_offset as $word;
+                    p[3] = ((node_depth as $word) << 16) ^
+                        (($bytes::to_usize() as $word) << 24);
+                }
+                _ => unreachable!(),
+            }
+            p
+        }
+
+        /// Feeds the blocks of the `i`-th leaf in `data`, which consists of
+        /// whole stripes, to `leaf`.
+        fn update_leaf(leaf: &mut $leaf, i: usize, data: &[u8]) {
+            for stripe in data.chunks(STRIPE) {
+                leaf.update(&stripe[i * BLOCK..(i + 1) * BLOCK]);
+            }
+        }
+
+        /// Feeds the remaining stripes and the `i`-th block of the partial
+        /// stripe `buf` to the `i`-th leaf, and finalizes it.
+        fn finalize_leaf(leaf: &mut $leaf, i: usize, data: &[u8], buf: &[u8]) -> Output {
+            update_leaf(leaf, i, data);
+            let start = cmp::min(i * BLOCK, buf.len());
+            let end = cmp::min(start + BLOCK, buf.len());
+            leaf.update(&buf[start..end]);
+
+            let last_node = if i == DEGREE - 1 { !0 } else { 0 };
+            leaf.finalize_with_flag(last_node)
+        }
+
+        impl $state {
+            fn new_keyed(key: &[u8]) -> Self {
+                assert!(key.len() <= $bytes::to_usize());
+                let leaves = GenericArray::generate(|i| {
+                    $leaf::with_parameter_block_keyed(&param_block(key.len(), i, 0), key)
+                });
+                $state {
+                    leaves,
+                    buf: [0; STRIPE],
+                    buf_len: 0,
+                    key_len: key.len(),
+                    #[cfg(feature = "rayon")]
+                    pending: std::vec::Vec::new(),
+                }
+            }
+
+            fn update(&mut self, mut data: &[u8]) {
+                let fill = STRIPE - self.buf_len;
+                if self.buf_len > 0 && data.len() >= fill {
+                    self.buf[self.buf_len..].copy_from_slice(&data[..fill]);
+                    data = &data[fill..];
+                    let buf = self.buf;
+                    self.update_leaves(&buf);
+                    self.buf_len = 0;
+                }
+
+                let n = data.len() - data.len() % STRIPE;
+                self.update_leaves(&data[..n]);
+
+                let rest = &data[n..];
+                self.buf[self.buf_len..self.buf_len + rest.len()].copy_from_slice(rest);
+                self.buf_len += rest.len();
+            }
+
+            #[cfg(not(feature = "rayon"))]
+            fn update_leaves(&mut self, data: &[u8]) {
+                for (i, leaf) in self.leaves.iter_mut().enumerate() {
+                    update_leaf(leaf, i, data);
+                }
+            }
+
+            #[cfg(feature = "rayon")]
+            fn update_leaves(&mut self, data: &[u8]) {
+                use rayon::prelude::*;
+
+                if data.is_empty() {
+                    return;
+                }
+                if !self.pending.is_empty() || data.len() < PARALLEL_MIN_LEN {
+                    self.pending.extend_from_slice(data);
+                    if self.pending.len() < PARALLEL_MIN_LEN {
+                        return;
+                    }
+                }
+
+                let data = if self.pending.is_empty() { data } else { &self.pending[..] };
+                #[cfg(test)]
+                PARALLEL_CALLS.with(|c| c.set(c.get() + 1));
+                self.leaves
+                    .par_iter_mut()
+                    .enumerate()
+                    .for_each(|(i, leaf)| update_leaf(leaf, i, data));
+                self.pending.clear();
+            }
+
+            #[cfg(not(feature = "rayon"))]
+            fn finalize_root(&mut self) -> Output {
+                let mut root = $leaf::with_parameter_block(&param_block(self.key_len, 0, 1));
+                let buf = &self.buf[..self.buf_len];
+                for (i, leaf) in self.leaves.iter_mut().enumerate() {
+                    root.update(&finalize_leaf(leaf, i, &[], buf));
+                }
+                root.finalize_with_flag(!0)
+            }
+
+            /// Finalizes the leaves on the rayon thread pool, each one after
+            /// hashing its share of the buffered stripes.
+            #[cfg(feature = "rayon")]
+            fn finalize_root(&mut self) -> Output {
+                use rayon::prelude::*;
+
+                let mut outputs = GenericArray::<Output, $degree>::default();
+                let (pending, buf) = (&self.pending[..], &self.buf[..self.buf_len]);
+                if pending.len() < PARALLEL_FINALIZE_MIN_LEN {
+                    for (i, (leaf, out)) in self.leaves.iter_mut().zip(outputs.iter_mut()).enumerate() {
+                        *out = finalize_leaf(leaf, i, pending, buf);
+                    }
+                } else {
+                    #[cfg(test)]
+                    PARALLEL_CALLS.with(|c| c.set(c.get() + 1));
+                    self.leaves
+                        .par_iter_mut()
+                        .zip(outputs.par_iter_mut())
+                        .enumerate()
+                        .for_each(|(i, (leaf, out))| *out = finalize_leaf(leaf, i, pending, buf));
+                }
+                self.pending.clear();
+
+                let mut root = $leaf::with_parameter_block(&param_block(self.key_len, 0, 1));
+                for out in outputs.iter() {
+                    root.update(out);
+                }
+                root.finalize_with_flag(!0)
+            }
+        }
+
+        impl Default for $state {
+            fn default() -> Self { Self::new_keyed(&[]) }
+        }
+
+        impl BlockInput for $state {
+            type BlockSize = $block_size;
+        }
+
+        impl Update for $state {
+            fn update(&mut self, data: impl AsRef<[u8]>) {
+                self.update(data.as_ref());
+            }
+        }
+
+        impl FixedOutputDirty for $state {
+            type OutputSize = $bytes;
+
+            fn finalize_into_dirty(&mut self, out: &mut Output) {
+                out.copy_from_slice(&self.finalize_root());
+            }
+        }
+
+        impl Reset for $state {
+            fn reset(&mut self) {
+                for leaf in self.leaves.iter_mut() {
+                    Reset::reset(leaf);
+                }
+                self.buf_len = 0;
+                #[cfg(feature = "rayon")]
+                self.pending.clear();
+            }
+        }
+
+        impl NewMac for $state {
+            type KeySize = $bytes;
+
+            fn new(key: &GenericArray<u8, $bytes>) -> Self {
+                Self::new_keyed(key)
+            }
+
+            fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+                if key.len() > $bytes::to_usize() {
+                    Err(InvalidKeyLength)
+                } else {
+                    Ok(Self::new_keyed(key))
+                }
+            }
+        }
+
+        impl Mac for $state {
+            type OutputSize = $bytes;
+
+            fn update(&mut self, data: &[u8]) { self.update(data); }
+
+            fn reset(&mut self) {
+                <Self as Reset>::reset(self)
+            }
+
+            fn finalize(mut self) -> crypto_mac::Output<Self> {
+                crypto_mac::Output::new(self.finalize_root())
+            }
+        }
+
+        opaque_debug::implement!($state);
+        digest::impl_write!($state);
+
+        #[cfg(all(test, feature = "rayon"))]
+        mod tests {
+            use super::*;
+
+            fn parallel_calls() -> usize {
+                PARALLEL_CALLS.with(|c| c.get())
+            }
+
+            #[test]
+            fn rayon_is_used() {
+                let data = std::vec![0x5Au8; PARALLEL_MIN_LEN + PARALLEL_FINALIZE_MIN_LEN + 100];
+
+                // small inputs are buffered, then hashed in parallel
+                let mut hasher = $state::default();
+                let mut chunks = data.chunks(1000);
+                for chunk in (&mut chunks).take(PARALLEL_MIN_LEN / 1000) {
+                    hasher.update(chunk);
+                }
+                assert_eq!(parallel_calls(), 0);
+                for chunk in chunks {
+                    hasher.update(chunk);
+                }
+                assert_eq!(parallel_calls(), 1);
+                let res = hasher.finalize_root();
+                assert_eq!(parallel_calls(), 2);
+
+                // large inputs are hashed in parallel directly
+                let mut hasher = $state::default();
+                hasher.update(&data[..]);
+                assert_eq!(parallel_calls(), 3);
+                assert_eq!(hasher.finalize_root(), res);
+                assert_eq!(parallel_calls(), 3);
+            }
+        }
+    }
+}
@@ -4,6 +4,7 @@ use digest::generic_array::typenum::{U32, U64};
 blake2_impl!(
     VarBlake2s,
     Blake2s,
+    Blake2sParams,
     u32,
     u32x4,
     U32,
@@ -13,6 +14,9 @@ blake2_impl!(
     8,
     7,
     BLAKE2S_IV,
+    compress_s,
+    detect_many_s,
     "Blake2s instance with a variable output.",
     "Blake2s instance with a fixed output.",
+    "Builder for the parameter block of `VarBlake2s`.",
 );
@@ -0,0 +1,12 @@
+use crate::blake2s::VarBlake2s;
+use digest::generic_array::typenum::{U32, U64, U8};
+
+blake2p_impl!(
+    Blake2sp,
+    VarBlake2s,
+    u32,
+    U32,
+    U64,
+    U8,
+    "Blake2sp instance, i.e. Blake2s with 8 leaves hashed in parallel.",
+);
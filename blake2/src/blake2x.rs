@@ -0,0 +1,192 @@
+macro_rules! blake2x_impl {
+    (
+        $state:ident, $reader:ident, $var:ident, $params:ident, $bytes:ident,
+        $block_size:ident, $xof_len:ident, $doc:expr, $reader_doc:expr,
+    ) => {
+
+        use digest::{Update, BlockInput, ExtendableOutputDirty, Reset, XofReader};
+        use digest::generic_array::GenericArray;
+        use digest::generic_array::typenum::Unsigned;
+        use core::cmp;
+        use $crate::ParamsError;
+
+        type Output = GenericArray<u8, $bytes>;
+
+        /// XOF length of an output of unknown length.
+        const UNKNOWN_LENGTH: $xof_len = !0;
+
+        #[derive(Clone)]
+        #[doc=$doc]
+        pub struct $state {
+            root: $var,
+            xof_len: $xof_len,
+            salt: Output,
+            persona: Output,
+        }
+
+        impl $state {
+            /// Creates a new hashing context for `output_len` bytes of output,
+            /// or for an output of unknown length if `None`.
+            pub fn new(output_len: Option<$xof_len>) -> Result<Self, ParamsError> {
+                Self::with_params(&[], &[], &[], output_len)
+            }
+
+            /// Creates a new hashing context with a key.
+            pub fn new_keyed(key: &[u8], output_len: Option<$xof_len>) -> Result<Self, ParamsError> {
+                Self::with_params(key, &[], &[], output_len)
+            }
+
+            /// Creates a new hashing context with key, salt and personalization.
+            ///
+            /// Returns an error if any of them is too long, or if `output_len`
+            /// is zero or the maximal value, which denotes an unknown length.
+            pub fn with_params(
+                key: &[u8], salt: &[u8], persona: &[u8], output_len: Option<$xof_len>,
+            ) -> Result<Self, ParamsError> {
+                let xof_len = match output_len {
+                    Some(len) if len == 0 || len == UNKNOWN_LENGTH => {
+                        return Err(ParamsError::DigestLength);
+                    }
+                    Some(len) => len,
+                    None => UNKNOWN_LENGTH,
+                };
+
+                // the XOF length is stored in the upper half of the node offset
+                let root = $params::new()
+                    .key(key)
+                    .salt(salt)
+                    .persona(persona)
+                    .node_offset(u64::from(xof_len) << 32)
+                    .build()?;
+
+                let mut state = $state {
+                    root,
+                    xof_len,
+                    salt: Output::default(),
+                    persona: Output::default(),
+                };
+                state.salt[..salt.len()].copy_from_slice(salt);
+                state.persona[..persona.len()].copy_from_slice(persona);
+                Ok(state)
+            }
+
+            /// Returns the output length, or `None` if it is unknown.
+            pub fn output_len(&self) -> Option<$xof_len> {
+                if self.xof_len == UNKNOWN_LENGTH {
+                    None
+                } else {
+                    Some(self.xof_len)
+                }
+            }
+        }
+
+        impl Default for $state {
+            fn default() -> Self {
+                Self::new(None).expect("default parameters are valid")
+            }
+        }
+
+        impl BlockInput for $state {
+            type BlockSize = $block_size;
+        }
+
+        impl Update for $state {
+            fn update(&mut self, data: impl AsRef<[u8]>) {
+                self.root.update(data.as_ref());
+            }
+        }
+
+        impl ExtendableOutputDirty for $state {
+            type Reader = $reader;
+
+            fn finalize_xof_dirty(&mut self) -> $reader {
+                $reader {
+                    h0: self.root.finalize_with_flag(0),
+                    xof_len: self.xof_len,
+                    salt: self.salt.clone(),
+                    persona: self.persona.clone(),
+                    node: 0,
+                    block: Output::default(),
+                    block_len: 0,
+                    pos: 0,
+                }
+            }
+        }
+
+        impl Reset for $state {
+            fn reset(&mut self) {
+                Reset::reset(&mut self.root);
+            }
+        }
+
+        opaque_debug::implement!($state);
+        digest::impl_write!($state);
+
+        #[derive(Clone)]
+        #[doc=$reader_doc]
+        ///
+        /// # Panics
+        ///
+        /// If more bytes than the output length are read.
+        pub struct $reader {
+            h0: Output,
+            xof_len: $xof_len,
+            salt: Output,
+            persona: Output,
+            node: u64,
+            block: Output,
+            block_len: usize,
+            pos: usize,
+        }
+
+        impl $reader {
+            fn next_block(&mut self) {
+                let n = $bytes::to_usize();
+                let block_len = if self.xof_len == UNKNOWN_LENGTH {
+                    assert!(self.node >> 32 == 0, "BLAKE2X output is exhausted");
+                    n
+                } else {
+                    let offset = self.node * n as u64;
+                    assert!(offset < u64::from(self.xof_len), "BLAKE2X output length exceeded");
+                    cmp::min(n as u64, u64::from(self.xof_len) - offset) as usize
+                };
+
+                let p = $params::new()
+                    .digest_length(block_len)
+                    .salt(&self.salt[..n / 4])
+                    .persona(&self.persona[..n / 4])
+                    .fanout(0)
+                    .max_depth(0)
+                    .leaf_length(n as u32)
+                    .node_offset(self.node | (u64::from(self.xof_len) << 32))
+                    .inner_length(n)
+                    .param_block();
+                let mut node = $var::with_parameter_block(&p);
+                node.update(&self.h0);
+
+                self.block = node.finalize_with_flag(0);
+                self.block_len = block_len;
+                self.pos = 0;
+                self.node += 1;
+            }
+        }
+
+        impl XofReader for $reader {
+            fn read(&mut self, buffer: &mut [u8]) {
+                let mut written = 0;
+                while written < buffer.len() {
+                    if self.pos == self.block_len {
+                        self.next_block();
+                    }
+                    let n = cmp::min(buffer.len() - written, self.block_len - self.pos);
+                    buffer[written..written + n]
+                        .copy_from_slice(&self.block[self.pos..self.pos + n]);
+                    self.pos += n;
+                    written += n;
+                }
+            }
+        }
+
+        opaque_debug::implement!($reader);
+    }
+}
@@ -0,0 +1,14 @@
+use crate::blake2b::{Blake2bParams, VarBlake2b};
+use digest::generic_array::typenum::{U128, U64};
+
+blake2x_impl!(
+    Blake2xb,
+    Blake2xbReader,
+    VarBlake2b,
+    Blake2bParams,
+    U64,
+    U128,
+    u32,
+    "BLAKE2Xb extendable-output function.",
+    "BLAKE2Xb XOF reader.",
+);
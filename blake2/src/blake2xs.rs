@@ -0,0 +1,14 @@
+use crate::blake2s::{Blake2sParams, VarBlake2s};
+use digest::generic_array::typenum::{U32, U64};
+
+blake2x_impl!(
+    Blake2xs,
+    Blake2xsReader,
+    VarBlake2s,
+    Blake2sParams,
+    U32,
+    U64,
+    u16,
+    "BLAKE2Xs extendable-output function.",
+    "BLAKE2Xs XOF reader.",
+);
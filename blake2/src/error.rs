@@ -0,0 +1,38 @@
+use core::fmt;
+
+/// The error type returned when building a hashing context from invalid
+/// parameters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParamsError {
+    /// The digest length is zero or larger than the output size.
+    DigestLength,
+    /// The key is longer than the output size.
+    KeyLength,
+    /// The salt is longer than a quarter of the output size.
+    SaltLength,
+    /// The personalization is longer than a quarter of the output size.
+    PersonaLength,
+    /// The maximal depth is zero.
+    MaxDepth,
+    /// The node offset does not fit into the parameter block.
+    NodeOffset,
+    /// The inner hash length is larger than the output size.
+    InnerLength,
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParamsError::DigestLength => "invalid digest length",
+            ParamsError::KeyLength => "key is too long",
+            ParamsError::SaltLength => "salt is too long",
+            ParamsError::PersonaLength => "personalization is too long",
+            ParamsError::MaxDepth => "maximal depth must not be zero",
+            ParamsError::NodeOffset => "node offset is too large",
+            ParamsError::InnerLength => "invalid inner hash length",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParamsError {}
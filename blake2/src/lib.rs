@@ -75,6 +75,92 @@
 //! hasher.verify(&code_bytes).unwrap();
 //! ```
 //!
+//! ## Parameters
+//!
+//! All fields of the BLAKE2 parameter block, including those of the tree
+//! hashing mode, can be set with `Blake2bParams` and `Blake2sParams`.
+//! Invalid parameters are reported as an error instead of a panic:
+//!
+//! ```rust
+//! use blake2::{Blake2bParams, ParamsError};
+//! use blake2::digest::{Update, VariableOutput};
+//!
+//! let mut hasher = Blake2bParams::new()
+//!     .digest_length(32)
+//!     .persona(b"my app")
+//!     .fanout(2)
+//!     .max_depth(2)
+//!     .node_offset(1)
+//!     .inner_length(64)
+//!     .build()
+//!     .unwrap();
+//! hasher.update(b"my_input");
+//! hasher.finalize_variable(|res| assert_eq!(res.len(), 32));
+//!
+//! let err = Blake2bParams::new().salt(&[0; 17]).build().unwrap_err();
+//! assert_eq!(err, ParamsError::SaltLength);
+//! ```
+//!
+//! ## Parallel modes
+//!
+//! `Blake2bp` and `Blake2sp` split the message between 4 and 8 instances of
+//! `Blake2b` and `Blake2s` respectively, and hash their outputs with a root
+//! instance. They produce different digests than the sequential functions,
+//! but can be computed faster on large inputs. Both support keys like their
+//! sequential counterparts.
+//!
+//! ```rust
+//! use blake2::{Blake2bp, Digest};
+//!
+//! let mut hasher = Blake2bp::new();
+//! hasher.update(&[0u8; 4096][..]);
+//! let res = hasher.finalize();
+//! ```
+//!
+//! With the `rayon` feature enabled, the leaves are hashed with one thread
+//! each. Input is buffered until at least 64 KiB can be hashed at once, and
+//! the remaining buffered input is hashed in parallel on finalization.
+//!
+//! ## Extendable output
+//!
+//! `Blake2xb` and `Blake2xs` are the BLAKE2X extendable-output functions.
+//! The output length is part of the hash, so it has to be passed on
+//! construction, or `None` if it is not known in advance:
+//!
+//! ```rust
+//! use blake2::Blake2xb;
+//! use blake2::digest::{ExtendableOutput, Update, XofReader};
+//!
+//! let mut hasher = Blake2xb::new_keyed(b"my key", Some(100)).unwrap();
+//! hasher.update(b"my_input");
+//! let mut reader = hasher.finalize_xof();
+//! let mut res = [0u8; 100];
+//! reader.read(&mut res);
+//! ```
+//!
+//! ## Hashing many messages
+//!
+//! `Blake2b::hash_many` and `Blake2s::hash_many` hash independent messages,
+//! each with its own parameters. With AVX2 they are hashed 4 or 8 at a time,
+//! one message per vector lane:
+//!
+//! ```rust
+//! use blake2::{Blake2s, Blake2sParams};
+//!
+//! let params = [Blake2sParams::new(), *Blake2sParams::new().key(b"key")];
+//! let mut outputs = [Default::default(); 2];
+//! Blake2s::hash_many(&params, &[b"chunk 1", b"chunk 2"], &mut outputs).unwrap();
+//! ```
+//!
+//! ## SIMD backends
+//!
+//! On x86 and x86-64 the compression function is computed with SSE4.1
+//! instructions, or AVX2 for BLAKE2b, if the CPU supports them, which is
+//! detected at runtime.
+//! Otherwise, or if the `force-soft` feature is enabled, the portable
+//! implementation is used. The nightly-only `simd` features also disable the
+//! runtime detection.
+//!
 //! # Acknowledgment
 //! Based on the [blake2-rfc][2] crate.
 //!
@@ -90,22 +176,43 @@
 #![cfg_attr(feature = "simd", feature(platform_intrinsics, repr_simd))]
 #![cfg_attr(feature = "simd_asm", feature(asm))]
 
-#[cfg(feature = "std")]
+#[cfg(any(feature = "std", feature = "rayon"))]
 extern crate std;
 
 mod as_bytes;
 mod consts;
+mod error;
 
 mod simd;
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(any(feature = "simd", feature = "force-soft")),
+))]
+mod x86;
 
+#[macro_use]
+mod many;
 #[macro_use]
 mod blake2;
+#[macro_use]
+mod blake2p;
+#[macro_use]
+mod blake2x;
 
 mod blake2b;
+mod blake2bp;
 mod blake2s;
+mod blake2sp;
+mod blake2xb;
+mod blake2xs;
 
 pub use crypto_mac;
 pub use digest::{self, Digest};
 
-pub use crate::blake2b::{Blake2b, VarBlake2b};
-pub use crate::blake2s::{Blake2s, VarBlake2s};
+pub use crate::blake2b::{Blake2b, Blake2bParams, VarBlake2b};
+pub use crate::blake2bp::Blake2bp;
+pub use crate::blake2s::{Blake2s, Blake2sParams, VarBlake2s};
+pub use crate::blake2sp::Blake2sp;
+pub use crate::blake2xb::{Blake2xb, Blake2xbReader};
+pub use crate::blake2xs::{Blake2xs, Blake2xsReader};
+pub use crate::error::ParamsError;
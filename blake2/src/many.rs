@@ -0,0 +1,168 @@
+/// Implements `hash_many` for the fixed output type. It is expanded by
+/// `blake2_impl!` and uses its `compress` function.
+macro_rules! hash_many_impl {
+    (
+        $fix_state:ident, $state:ident, $params:ident, $word:ident, $vec:ident,
+        $bytes:ident, $x86_many:ident,
+    ) => {
+
+        /// Maximum number of lanes supported by any backend.
+        const MAX_LANES: usize = 8;
+
+        /// Compression function which processes one block in each lane.
+        type CompressMany = fn(&mut [[$word; 8]], &[[$word; 16]], &[u64], &[$word], &[$word]);
+
+        /// A message which is being hashed in a lane.
+        struct Job<'a> {
+            /// Index of the message.
+            idx: usize,
+            /// Part of the message which has not been compressed yet.
+            rest: &'a [u8],
+            /// Whether the lane holds a key block which has not been
+            /// compressed yet.
+            key_block: bool,
+            digest_length: usize,
+            last_node: bool,
+        }
+
+        impl $fix_state {
+            /// Computes the hashes of independent messages, using the
+            /// parameters at the same index for each message, and writes them
+            /// into `outputs`.
+            ///
+            /// The parameters can set a different key and digest length for
+            /// each message. The first `digest_length` bytes of an output hold
+            /// the hash, the rest is zeroed. On x86 CPUs with AVX2 several
+            /// messages are hashed at once, one per vector lane. Results are
+            /// identical to hashing each message with the hashing context
+            /// built from its parameters.
+            ///
+            /// Returns an error without writing any output if any of the
+            /// parameters is invalid.
+            ///
+            /// # Panics
+            ///
+            /// If `params`, `inputs` and `outputs` have different lengths.
+            pub fn hash_many(
+                params: &[$params<'_>], inputs: &[&[u8]], outputs: &mut [Output],
+            ) -> Result<(), ParamsError> {
+                assert_eq!(
+                    params.len(),
+                    inputs.len(),
+                    "number of parameters must be equal to number of inputs"
+                );
+                assert_eq!(
+                    outputs.len(),
+                    inputs.len(),
+                    "number of outputs must be equal to number of inputs"
+                );
+                for p in params {
+                    p.build()?;
+                }
+
+                #[cfg(all(
+                    any(target_arch = "x86", target_arch = "x86_64"),
+                    not(any(feature = "simd", feature = "force-soft")),
+                ))]
+                {
+                    if let Some((lanes, compress)) = $crate::x86::$x86_many() {
+                        hash_lanes(lanes, compress, params, inputs, outputs);
+                        return Ok(());
+                    }
+                }
+
+                hash_lanes(1, compress_one, params, inputs, outputs);
+                Ok(())
+            }
+        }
+
+        fn to_words(h: &[$vec; 2]) -> [$word; 8] {
+            [h[0].0, h[0].1, h[0].2, h[0].3, h[1].0, h[1].1, h[1].2, h[1].3]
+        }
+
+        fn compress_one(
+            h: &mut [[$word; 8]], m: &[[$word; 16]], t: &[u64], f0: &[$word], f1: &[$word],
+        ) {
+            let w = &mut h[0];
+            let mut v = [$vec::new(w[0], w[1], w[2], w[3]), $vec::new(w[4], w[5], w[6], w[7])];
+            compress(&mut v, &m[0], t[0], f0[0], f1[0]);
+            *w = to_words(&v);
+        }
+
+        /// Hashes `inputs` with the given `compress` function using `lanes`
+        /// lanes.
+        ///
+        /// As soon as a lane finishes its message, it picks up the next one,
+        /// so messages of unequal length keep all lanes busy.
+        fn hash_lanes(
+            lanes: usize, compress: CompressMany,
+            params: &[$params<'_>], inputs: &[&[u8]], outputs: &mut [Output],
+        ) {
+            debug_assert!(lanes <= MAX_LANES);
+            let block = 2 * $bytes::to_usize();
+
+            let mut h = [[0 as $word; 8]; MAX_LANES];
+            let mut m = [[0 as $word; 16]; MAX_LANES];
+            let mut t = [0u64; MAX_LANES];
+            let mut f0 = [0 as $word; MAX_LANES];
+            let mut f1 = [0 as $word; MAX_LANES];
+            let mut jobs: [Option<Job<'_>>; MAX_LANES] = Default::default();
+            let mut next = 0;
+
+            loop {
+                let mut active = false;
+                for lane in 0..lanes {
+                    if jobs[lane].is_none() && next < inputs.len() {
+                        let state = params[next].build().expect("parameters are validated");
+                        h[lane] = to_words(&state.h);
+                        m[lane] = state.m;
+                        t[lane] = state.t;
+                        jobs[lane] = Some(Job {
+                            idx: next,
+                            rest: inputs[next],
+                            key_block: state.t != 0,
+                            digest_length: state.n,
+                            last_node: state.last_node,
+                        });
+                        next += 1;
+                    }
+
+                    if let Some(job) = jobs[lane].as_mut() {
+                        if job.key_block {
+                            job.key_block = false;
+                        } else {
+                            let n = cmp::min(job.rest.len(), block);
+                            m[lane] = [0; 16];
+                            copy(&job.rest[..n], m[lane].as_mut_bytes());
+                            t[lane] += n as u64;
+                            job.rest = &job.rest[n..];
+                        }
+
+                        let last = job.rest.is_empty();
+                        f0[lane] = if last { !0 } else { 0 };
+                        f1[lane] = if last && job.last_node { !0 } else { 0 };
+                        active = true;
+                    }
+                }
+                if !active {
+                    break;
+                }
+
+                compress(&mut h[..lanes], &m[..lanes], &t[..lanes], &f0[..lanes], &f1[..lanes]);
+
+                for lane in 0..lanes {
+                    if f0[lane] == 0 {
+                        continue;
+                    }
+                    if let Some(job) = jobs[lane].take() {
+                        let mut words = h[lane];
+                        words.iter_mut().for_each(|w| *w = w.to_le());
+                        let out = &mut outputs[job.idx];
+                        *out = Output::default();
+                        copy(&words.as_bytes()[..job.digest_length], out);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,550 @@
+//! BLAKE2 compression functions written with SSE4.1 and AVX2 intrinsics.
+//!
+//! BLAKE2b keeps each row of the state in one AVX2 vector, or in a pair of
+//! SSE vectors. BLAKE2s keeps each row in one SSE vector, which AVX2 does
+//! not improve on for a single message. x86 is little-endian, so the message
+//! words are used as they are.
+#![allow(clippy::many_single_char_names)]
+
+use crate::consts::{BLAKE2B_IV, BLAKE2S_IV, SIGMA};
+use crate::simd::{u32x4, u64x4};
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Compresses one BLAKE2b block. Returns `false` if the CPU supports neither
+/// AVX2 nor SSE4.1, in which case the portable implementation has to be used.
+pub fn compress_b(h: &mut [u64x4; 2], m: &[u64; 16], t: u64, f0: u64, f1: u64) -> bool {
+    // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
+    // after stabilization
+    if cpuid_bool::cpuid_bool!("avx2") {
+        unsafe { compress_b_avx2(h, m, t, f0, f1) };
+        true
+    } else if cpuid_bool::cpuid_bool!("sse4.1") {
+        unsafe { compress_b_sse41(h, m, t, f0, f1) };
+        true
+    } else {
+        false
+    }
+}
+
+/// Compresses one BLAKE2s block. Returns `false` if the CPU does not support
+/// SSE4.1, in which case the portable implementation has to be used.
+pub fn compress_s(h: &mut [u32x4; 2], m: &[u32; 16], t: u64, f0: u32, f1: u32) -> bool {
+    if cpuid_bool::cpuid_bool!("sse4.1") {
+        unsafe { compress_s_sse41(h, m, t, f0, f1) };
+        true
+    } else {
+        false
+    }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn compress_b_avx2(h: &mut [u64x4; 2], m: &[u64; 16], t: u64, f0: u64, f1: u64) {
+    let rot24 = _mm256_setr_epi8(
+        3, 4, 5, 6, 7, 0, 1, 2, 11, 12, 13, 14, 15, 8, 9, 10, 3, 4, 5, 6, 7, 0, 1, 2, 11, 12, 13,
+        14, 15, 8, 9, 10,
+    );
+    let rot16 = _mm256_setr_epi8(
+        2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12, 13, 14, 15, 8, 9, 2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12,
+        13, 14, 15, 8, 9,
+    );
+
+    macro_rules! load {
+        ($w0:expr, $w1:expr, $w2:expr, $w3:expr) => {
+            _mm256_set_epi64x($w3 as i64, $w2 as i64, $w1 as i64, $w0 as i64)
+        };
+    }
+
+    macro_rules! g {
+        ($a:ident, $b:ident, $c:ident, $d:ident, $m0:expr, $m1:expr) => {
+            $a = _mm256_add_epi64(_mm256_add_epi64($a, $b), $m0);
+            $d = _mm256_shuffle_epi32(_mm256_xor_si256($d, $a), 0xB1);
+            $c = _mm256_add_epi64($c, $d);
+            $b = _mm256_shuffle_epi8(_mm256_xor_si256($b, $c), rot24);
+
+            $a = _mm256_add_epi64(_mm256_add_epi64($a, $b), $m1);
+            $d = _mm256_shuffle_epi8(_mm256_xor_si256($d, $a), rot16);
+            $c = _mm256_add_epi64($c, $d);
+            $b = _mm256_xor_si256($b, $c);
+            $b = _mm256_or_si256(_mm256_srli_epi64($b, 63), _mm256_add_epi64($b, $b));
+        };
+    }
+
+    let h0 = load!(h[0].0, h[0].1, h[0].2, h[0].3);
+    let h1 = load!(h[1].0, h[1].1, h[1].2, h[1].3);
+
+    let mut a = h0;
+    let mut b = h1;
+    let mut c = load!(BLAKE2B_IV[0], BLAKE2B_IV[1], BLAKE2B_IV[2], BLAKE2B_IV[3]);
+    let mut d = load!(
+        BLAKE2B_IV[4] ^ t,
+        BLAKE2B_IV[5],
+        BLAKE2B_IV[6] ^ f0,
+        BLAKE2B_IV[7] ^ f1
+    );
+
+    for s in SIGMA.iter() {
+        g!(
+            a,
+            b,
+            c,
+            d,
+            load!(m[s[0]], m[s[2]], m[s[4]], m[s[6]]),
+            load!(m[s[1]], m[s[3]], m[s[5]], m[s[7]])
+        );
+        b = _mm256_permute4x64_epi64(b, 0x39);
+        c = _mm256_permute4x64_epi64(c, 0x4E);
+        d = _mm256_permute4x64_epi64(d, 0x93);
+
+        g!(
+            a,
+            b,
+            c,
+            d,
+            load!(m[s[8]], m[s[10]], m[s[12]], m[s[14]]),
+            load!(m[s[9]], m[s[11]], m[s[13]], m[s[15]])
+        );
+        b = _mm256_permute4x64_epi64(b, 0x93);
+        c = _mm256_permute4x64_epi64(c, 0x4E);
+        d = _mm256_permute4x64_epi64(d, 0x39);
+    }
+
+    let h0 = _mm256_xor_si256(h0, _mm256_xor_si256(a, c));
+    let h1 = _mm256_xor_si256(h1, _mm256_xor_si256(b, d));
+
+    let mut out = [0u64; 8];
+    _mm256_storeu_si256(out.as_mut_ptr() as *mut __m256i, h0);
+    _mm256_storeu_si256(out.as_mut_ptr().add(4) as *mut __m256i, h1);
+    h[0] = u64x4::new(out[0], out[1], out[2], out[3]);
+    h[1] = u64x4::new(out[4], out[5], out[6], out[7]);
+}
+
+#[target_feature(enable = "sse4.1")]
+unsafe fn compress_b_sse41(h: &mut [u64x4; 2], m: &[u64; 16], t: u64, f0: u64, f1: u64) {
+    let rot24 = _mm_setr_epi8(3, 4, 5, 6, 7, 0, 1, 2, 11, 12, 13, 14, 15, 8, 9, 10);
+    let rot16 = _mm_setr_epi8(2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12, 13, 14, 15, 8, 9);
+
+    macro_rules! load {
+        ($w0:expr, $w1:expr) => {
+            _mm_set_epi64x($w1 as i64, $w0 as i64)
+        };
+    }
+
+    // each row is split into the low and the high pair of words
+    macro_rules! g_half {
+        ($a:expr, $b:expr, $c:expr, $d:expr, $m0:expr, $m1:expr) => {
+            $a = _mm_add_epi64(_mm_add_epi64($a, $b), $m0);
+            $d = _mm_shuffle_epi32(_mm_xor_si128($d, $a), 0xB1);
+            $c = _mm_add_epi64($c, $d);
+            $b = _mm_shuffle_epi8(_mm_xor_si128($b, $c), rot24);
+
+            $a = _mm_add_epi64(_mm_add_epi64($a, $b), $m1);
+            $d = _mm_shuffle_epi8(_mm_xor_si128($d, $a), rot16);
+            $c = _mm_add_epi64($c, $d);
+            $b = _mm_xor_si128($b, $c);
+            $b = _mm_or_si128(_mm_srli_epi64($b, 63), _mm_add_epi64($b, $b));
+        };
+    }
+
+    // rotate the words of a row by one position
+    macro_rules! rotate_left_1 {
+        ($x:ident) => {
+            $x = [
+                _mm_alignr_epi8($x[1], $x[0], 8),
+                _mm_alignr_epi8($x[0], $x[1], 8),
+            ];
+        };
+    }
+    macro_rules! rotate_right_1 {
+        ($x:ident) => {
+            $x = [
+                _mm_alignr_epi8($x[0], $x[1], 8),
+                _mm_alignr_epi8($x[1], $x[0], 8),
+            ];
+        };
+    }
+
+    let h0 = [load!(h[0].0, h[0].1), load!(h[0].2, h[0].3)];
+    let h1 = [load!(h[1].0, h[1].1), load!(h[1].2, h[1].3)];
+
+    let mut a = h0;
+    let mut b = h1;
+    let mut c = [
+        load!(BLAKE2B_IV[0], BLAKE2B_IV[1]),
+        load!(BLAKE2B_IV[2], BLAKE2B_IV[3]),
+    ];
+    let mut d = [
+        load!(BLAKE2B_IV[4] ^ t, BLAKE2B_IV[5]),
+        load!(BLAKE2B_IV[6] ^ f0, BLAKE2B_IV[7] ^ f1),
+    ];
+
+    macro_rules! g {
+        ($s:expr, $i:expr) => {
+            g_half!(
+                a[0],
+                b[0],
+                c[0],
+                d[0],
+                load!(m[$s[$i]], m[$s[$i + 2]]),
+                load!(m[$s[$i + 1]], m[$s[$i + 3]])
+            );
+            g_half!(
+                a[1],
+                b[1],
+                c[1],
+                d[1],
+                load!(m[$s[$i + 4]], m[$s[$i + 6]]),
+                load!(m[$s[$i + 5]], m[$s[$i + 7]])
+            );
+        };
+    }
+
+    for s in SIGMA.iter() {
+        g!(s, 0);
+        rotate_left_1!(b);
+        c = [c[1], c[0]];
+        rotate_right_1!(d);
+
+        g!(s, 8);
+        rotate_right_1!(b);
+        c = [c[1], c[0]];
+        rotate_left_1!(d);
+    }
+
+    let mut out = [0u64; 8];
+    let out_ptr = out.as_mut_ptr() as *mut __m128i;
+    for i in 0..2 {
+        let h0 = _mm_xor_si128(h0[i], _mm_xor_si128(a[i], c[i]));
+        let h1 = _mm_xor_si128(h1[i], _mm_xor_si128(b[i], d[i]));
+        _mm_storeu_si128(out_ptr.add(i), h0);
+        _mm_storeu_si128(out_ptr.add(i + 2), h1);
+    }
+    h[0] = u64x4::new(out[0], out[1], out[2], out[3]);
+    h[1] = u64x4::new(out[4], out[5], out[6], out[7]);
+}
+
+#[target_feature(enable = "sse4.1")]
+unsafe fn compress_s_sse41(h: &mut [u32x4; 2], m: &[u32; 16], t: u64, f0: u32, f1: u32) {
+    let rot16 = _mm_setr_epi8(2, 3, 0, 1, 6, 7, 4, 5, 10, 11, 8, 9, 14, 15, 12, 13);
+    let rot8 = _mm_setr_epi8(1, 2, 3, 0, 5, 6, 7, 4, 9, 10, 11, 8, 13, 14, 15, 12);
+
+    macro_rules! load {
+        ($w0:expr, $w1:expr, $w2:expr, $w3:expr) => {
+            _mm_set_epi32($w3 as i32, $w2 as i32, $w1 as i32, $w0 as i32)
+        };
+    }
+
+    macro_rules! g {
+        ($a:ident, $b:ident, $c:ident, $d:ident, $m0:expr, $m1:expr) => {
+            $a = _mm_add_epi32(_mm_add_epi32($a, $b), $m0);
+            $d = _mm_shuffle_epi8(_mm_xor_si128($d, $a), rot16);
+            $c = _mm_add_epi32($c, $d);
+            $b = _mm_xor_si128($b, $c);
+            $b = _mm_or_si128(_mm_srli_epi32($b, 12), _mm_slli_epi32($b, 20));
+
+            $a = _mm_add_epi32(_mm_add_epi32($a, $b), $m1);
+            $d = _mm_shuffle_epi8(_mm_xor_si128($d, $a), rot8);
+            $c = _mm_add_epi32($c, $d);
+            $b = _mm_xor_si128($b, $c);
+            $b = _mm_or_si128(_mm_srli_epi32($b, 7), _mm_slli_epi32($b, 25));
+        };
+    }
+
+    let h0 = load!(h[0].0, h[0].1, h[0].2, h[0].3);
+    let h1 = load!(h[1].0, h[1].1, h[1].2, h[1].3);
+
+    let mut a = h0;
+    let mut b = h1;
+    let mut c = load!(BLAKE2S_IV[0], BLAKE2S_IV[1], BLAKE2S_IV[2], BLAKE2S_IV[3]);
+    let mut d = load!(
+        BLAKE2S_IV[4] ^ (t as u32),
+        BLAKE2S_IV[5] ^ ((t >> 32) as u32),
+        BLAKE2S_IV[6] ^ f0,
+        BLAKE2S_IV[7] ^ f1
+    );
+
+    for s in SIGMA[..10].iter() {
+        g!(
+            a,
+            b,
+            c,
+            d,
+            load!(m[s[0]], m[s[2]], m[s[4]], m[s[6]]),
+            load!(m[s[1]], m[s[3]], m[s[5]], m[s[7]])
+        );
+        b = _mm_shuffle_epi32(b, 0x39);
+        c = _mm_shuffle_epi32(c, 0x4E);
+        d = _mm_shuffle_epi32(d, 0x93);
+
+        g!(
+            a,
+            b,
+            c,
+            d,
+            load!(m[s[8]], m[s[10]], m[s[12]], m[s[14]]),
+            load!(m[s[9]], m[s[11]], m[s[13]], m[s[15]])
+        );
+        b = _mm_shuffle_epi32(b, 0x93);
+        c = _mm_shuffle_epi32(c, 0x4E);
+        d = _mm_shuffle_epi32(d, 0x39);
+    }
+
+    let h0 = _mm_xor_si128(h0, _mm_xor_si128(a, c));
+    let h1 = _mm_xor_si128(h1, _mm_xor_si128(b, d));
+
+    let mut out = [0u32; 8];
+    _mm_storeu_si128(out.as_mut_ptr() as *mut __m128i, h0);
+    _mm_storeu_si128(out.as_mut_ptr().add(4) as *mut __m128i, h1);
+    h[0] = u32x4::new(out[0], out[1], out[2], out[3]);
+    h[1] = u32x4::new(out[4], out[5], out[6], out[7]);
+}
+
+/// Compression function which processes one block in each of several lanes.
+pub type CompressMany<W> = fn(&mut [[W; 8]], &[[W; 16]], &[u64], &[W], &[W]);
+
+/// Returns the number of lanes and the compression function for hashing
+/// several BLAKE2b messages at once, or `None` if AVX2 is not supported.
+pub fn detect_many_b() -> Option<(usize, CompressMany<u64>)> {
+    if cpuid_bool::cpuid_bool!("avx2") {
+        Some((4, compress_many_b))
+    } else {
+        None
+    }
+}
+
+/// Returns the number of lanes and the compression function for hashing
+/// several BLAKE2s messages at once, or `None` if AVX2 is not supported.
+pub fn detect_many_s() -> Option<(usize, CompressMany<u32>)> {
+    if cpuid_bool::cpuid_bool!("avx2") {
+        Some((8, compress_many_s))
+    } else {
+        None
+    }
+}
+
+fn compress_many_b(h: &mut [[u64; 8]], m: &[[u64; 16]], t: &[u64], f0: &[u64], f1: &[u64]) {
+    unsafe { compress_many_b_avx2(h, m, t, f0, f1) }
+}
+
+fn compress_many_s(h: &mut [[u32; 8]], m: &[[u32; 16]], t: &[u64], f0: &[u32], f1: &[u32]) {
+    unsafe { compress_many_s_avx2(h, m, t, f0, f1) }
+}
+
+/// Implements a compression function which keeps word `i` of the state of
+/// all lanes in vector `i`, so that the rounds need no shuffles.
+macro_rules! impl_compress_many {
+    (
+        $name:ident, $word:ident, $lanes:expr, $rounds:expr, $iv:ident,
+        $add:ident, $srli:ident, $slli:ident, $t1:expr,
+        $R1:expr, $L1:expr, $R2:expr, $L2:expr, $R3:expr, $L3:expr, $R4:expr, $L4:expr
+    ) => {
+        #[target_feature(enable = "avx2")]
+        unsafe fn $name(
+            h: &mut [[$word; 8]],
+            m: &[[$word; 16]],
+            t: &[u64],
+            f0: &[$word],
+            f1: &[$word],
+        ) {
+            // load one word of every lane into a vector
+            macro_rules! gather {
+                ($i:ident => $e:expr) => {{
+                    let mut w = [0 as $word; $lanes];
+                    for ($i, w) in w.iter_mut().enumerate() {
+                        *w = $e;
+                    }
+                    _mm256_loadu_si256(w.as_ptr() as *const __m256i)
+                }};
+            }
+
+            let mut v = [_mm256_setzero_si256(); 16];
+            for j in 0..8 {
+                v[j] = gather!(i => h[i][j]);
+                v[j + 8] = gather!(_i => $iv[j]);
+            }
+            v[12] = _mm256_xor_si256(v[12], gather!(i => t[i] as $word));
+            v[13] = _mm256_xor_si256(v[13], gather!(i => $t1(t[i])));
+            v[14] = _mm256_xor_si256(v[14], gather!(i => f0[i]));
+            v[15] = _mm256_xor_si256(v[15], gather!(i => f1[i]));
+
+            let mut msg = [_mm256_setzero_si256(); 16];
+            for (j, msg) in msg.iter_mut().enumerate() {
+                *msg = gather!(i => m[i][j]);
+            }
+
+            macro_rules! rotr {
+                ($x:expr, $r:expr, $l:expr) => {
+                    _mm256_or_si256($srli($x, $r), $slli($x, $l))
+                };
+            }
+
+            macro_rules! g {
+                ($a:expr, $b:expr, $c:expr, $d:expr, $x:expr, $y:expr) => {
+                    v[$a] = $add($add(v[$a], v[$b]), $x);
+                    v[$d] = rotr!(_mm256_xor_si256(v[$d], v[$a]), $R1, $L1);
+                    v[$c] = $add(v[$c], v[$d]);
+                    v[$b] = rotr!(_mm256_xor_si256(v[$b], v[$c]), $R2, $L2);
+                    v[$a] = $add($add(v[$a], v[$b]), $y);
+                    v[$d] = rotr!(_mm256_xor_si256(v[$d], v[$a]), $R3, $L3);
+                    v[$c] = $add(v[$c], v[$d]);
+                    v[$b] = rotr!(_mm256_xor_si256(v[$b], v[$c]), $R4, $L4);
+                };
+            }
+
+            for s in SIGMA[..$rounds].iter() {
+                g!(0, 4, 8, 12, msg[s[0]], msg[s[1]]);
+                g!(1, 5, 9, 13, msg[s[2]], msg[s[3]]);
+                g!(2, 6, 10, 14, msg[s[4]], msg[s[5]]);
+                g!(3, 7, 11, 15, msg[s[6]], msg[s[7]]);
+
+                g!(0, 5, 10, 15, msg[s[8]], msg[s[9]]);
+                g!(1, 6, 11, 12, msg[s[10]], msg[s[11]]);
+                g!(2, 7, 8, 13, msg[s[12]], msg[s[13]]);
+                g!(3, 4, 9, 14, msg[s[14]], msg[s[15]]);
+            }
+
+            for j in 0..8 {
+                let mut w = [0 as $word; $lanes];
+                let x = _mm256_xor_si256(v[j], v[j + 8]);
+                _mm256_storeu_si256(w.as_mut_ptr() as *mut __m256i, x);
+                for (i, w) in w.iter().enumerate() {
+                    h[i][j] ^= w;
+                }
+            }
+        }
+    };
+}
+
+impl_compress_many!(
+    compress_many_b_avx2,
+    u64,
+    4,
+    12,
+    BLAKE2B_IV,
+    _mm256_add_epi64,
+    _mm256_srli_epi64,
+    _mm256_slli_epi64,
+    |_| 0,
+    32,
+    32,
+    24,
+    40,
+    16,
+    48,
+    63,
+    1
+);
+
+impl_compress_many!(
+    compress_many_s_avx2,
+    u32,
+    8,
+    10,
+    BLAKE2S_IV,
+    _mm256_add_epi32,
+    _mm256_srli_epi32,
+    _mm256_slli_epi32,
+    |t: u64| (t >> 32) as u32,
+    16,
+    16,
+    12,
+    20,
+    8,
+    24,
+    7,
+    25
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pseudo-random words from xorshift64.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    type KernelB = unsafe fn(&mut [u64x4; 2], &[u64; 16], u64, u64, u64);
+
+    fn check_b(compress: KernelB) {
+        let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+        for i in 0..64 {
+            let mut w = [0u64; 8];
+            w.iter_mut().for_each(|v| *v = rng.next());
+            let mut m = [0u64; 16];
+            m.iter_mut().for_each(|v| *v = rng.next());
+            let t = rng.next();
+            let f0 = if i % 2 == 0 { !0 } else { 0 };
+            let f1 = if i % 4 == 0 { !0 } else { 0 };
+
+            let mut h = [
+                u64x4::new(w[0], w[1], w[2], w[3]),
+                u64x4::new(w[4], w[5], w[6], w[7]),
+            ];
+            let mut expected = h;
+            crate::blake2b::compress_soft(&mut expected, &m, t, f0, f1);
+            unsafe { compress(&mut h, &m, t, f0, f1) };
+            for j in 0..2 {
+                assert_eq!(
+                    (h[j].0, h[j].1, h[j].2, h[j].3),
+                    (expected[j].0, expected[j].1, expected[j].2, expected[j].3),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn compress_b_avx2_matches_soft() {
+        if cpuid_bool::cpuid_bool!("avx2") {
+            check_b(compress_b_avx2);
+        }
+    }
+
+    #[test]
+    fn compress_b_sse41_matches_soft() {
+        if cpuid_bool::cpuid_bool!("sse4.1") {
+            check_b(compress_b_sse41);
+        }
+    }
+
+    #[test]
+    fn compress_s_sse41_matches_soft() {
+        if !cpuid_bool::cpuid_bool!("sse4.1") {
+            return;
+        }
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        for i in 0..64 {
+            let mut w = [0u32; 8];
+            w.iter_mut().for_each(|v| *v = rng.next() as u32);
+            let mut m = [0u32; 16];
+            m.iter_mut().for_each(|v| *v = rng.next() as u32);
+            let t = rng.next();
+            let f0 = if i % 2 == 0 { !0 } else { 0 };
+            let f1 = if i % 4 == 0 { !0 } else { 0 };
+
+            let mut h = [
+                u32x4::new(w[0], w[1], w[2], w[3]),
+                u32x4::new(w[4], w[5], w[6], w[7]),
+            ];
+            let mut expected = h;
+            crate::blake2s::compress_soft(&mut expected, &m, t, f0, f1);
+            unsafe { compress_s_sse41(&mut h, &m, t, f0, f1) };
+            for j in 0..2 {
+                assert_eq!(
+                    (h[j].0, h[j].1, h[j].2, h[j].3),
+                    (expected[j].0, expected[j].1, expected[j].2, expected[j].3),
+                );
+            }
+        }
+    }
+}
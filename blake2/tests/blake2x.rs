@@ -0,0 +1,80 @@
+use blake2::digest::dev::blobby::Blob3Iterator;
+use blake2::digest::dev::xof_test;
+use blake2::digest::{new_test, ExtendableOutput, Update, XofReader};
+use blake2::{Blake2xb, Blake2xs, ParamsError};
+
+new_test!(blake2xb_xof, "blake2xb/xof", Blake2xb, xof_test);
+new_test!(blake2xs_xof, "blake2xs/xof", Blake2xs, xof_test);
+
+/// Keyed known-length vectors of the reference `blake2xb-kat.txt` and
+/// `blake2xs-kat.txt` from <https://github.com/BLAKE2/BLAKE2>, converted to
+/// blobby: the key is `00 01 ..`, the input is `00 01 .. ff` and the output
+/// length runs from 1 to 256 bytes.
+#[test]
+fn blake2xb_kat() {
+    let data = include_bytes!("data/blake2xb/kat.blb");
+    for (i, row) in Blob3Iterator::new(data).unwrap().enumerate() {
+        let (key, input, output) = (row[0], row[1], row[2]);
+        let mut hasher = Blake2xb::new_keyed(key, Some(output.len() as u32)).unwrap();
+        hasher.update(input);
+        let mut buf = vec![0; output.len()];
+        hasher.finalize_xof().read(&mut buf);
+        assert_eq!(buf, output, "test #{} failed", i);
+    }
+}
+
+#[test]
+fn blake2xs_kat() {
+    let data = include_bytes!("data/blake2xs/kat.blb");
+    for (i, row) in Blob3Iterator::new(data).unwrap().enumerate() {
+        let (key, input, output) = (row[0], row[1], row[2]);
+        let mut hasher = Blake2xs::new_keyed(key, Some(output.len() as u16)).unwrap();
+        hasher.update(input);
+        let mut buf = vec![0; output.len()];
+        hasher.finalize_xof().read(&mut buf);
+        assert_eq!(buf, output, "test #{} failed", i);
+    }
+}
+
+#[test]
+fn output_len() {
+    assert_eq!(Blake2xb::default().output_len(), None);
+    assert_eq!(Blake2xs::new(Some(100)).unwrap().output_len(), Some(100));
+    assert_eq!(
+        Blake2xb::new(Some(0)).unwrap_err(),
+        ParamsError::DigestLength
+    );
+    assert_eq!(
+        Blake2xs::new(Some(!0)).unwrap_err(),
+        ParamsError::DigestLength
+    );
+    assert_eq!(
+        Blake2xb::with_params(&[], &[0; 17], &[], None).unwrap_err(),
+        ParamsError::SaltLength
+    );
+    assert_eq!(
+        Blake2xs::new_keyed(&[0; 33], None).unwrap_err(),
+        ParamsError::KeyLength
+    );
+
+    // the output length is part of every parameter block
+    let mut short = [0; 64];
+    let mut long = [0; 64];
+    Blake2xb::new(Some(64))
+        .unwrap()
+        .finalize_xof()
+        .read(&mut short);
+    Blake2xb::new(Some(65))
+        .unwrap()
+        .finalize_xof()
+        .read(&mut long);
+    assert_ne!(short, long);
+}
+
+#[test]
+#[should_panic(expected = "BLAKE2X output length exceeded")]
+fn read_past_end() {
+    let mut reader = Blake2xs::new(Some(40)).unwrap().finalize_xof();
+    let mut buf = [0; 41];
+    reader.read(&mut buf);
+}
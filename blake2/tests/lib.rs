@@ -16,3 +16,5 @@ new_test!(
     blake2::VarBlake2s,
     variable_test
 );
+new_test!(blake2bp_fixed, "blake2bp/fixed", blake2::Blake2bp, digest_test);
+new_test!(blake2sp_fixed, "blake2sp/fixed", blake2::Blake2sp, digest_test);
@@ -4,3 +4,5 @@ use crypto_mac::new_test;
 
 new_test!(blake2b_mac, "blake2b/mac", blake2::Blake2b);
 new_test!(blake2s_mac, "blake2s/mac", blake2::Blake2s);
+new_test!(blake2bp_mac, "blake2bp/mac", blake2::Blake2bp);
+new_test!(blake2sp_mac, "blake2sp/mac", blake2::Blake2sp);
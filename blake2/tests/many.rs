@@ -0,0 +1,105 @@
+use blake2::digest::{Update, VariableOutput};
+use blake2::{Blake2b, Blake2bParams, Blake2s, Blake2sParams, ParamsError};
+
+const BLAKE2S_BLOCK: usize = 64;
+const BLAKE2B_BLOCK: usize = 128;
+
+fn messages() -> Vec<Vec<u8>> {
+    // BLAKE2 only finalizes a full last block once no more input follows,
+    // so check lengths on both sides of one, two and eight blocks of both
+    // variants. Neighbouring lanes then need a different number of
+    // compressions.
+    let mut lens = vec![0];
+    for &block in [BLAKE2S_BLOCK, BLAKE2B_BLOCK].iter() {
+        for &blocks in [1, 2, 8].iter() {
+            let n = blocks * block;
+            lens.extend_from_slice(&[n - 1, n, n + 1]);
+        }
+    }
+    lens.iter()
+        .enumerate()
+        .map(|(j, &n)| (0..n).map(|i| (i * 31 + j) as u8).collect())
+        .collect()
+}
+
+fn keys() -> Vec<Vec<u8>> {
+    (0..messages().len())
+        .map(|j| (0..j * 3 % 33).map(|i| (i + j) as u8).collect())
+        .collect()
+}
+
+#[test]
+fn blake2b_hash_many() {
+    let msgs = messages();
+    let keys = keys();
+    let inputs: Vec<&[u8]> = msgs.iter().map(|m| &m[..]).collect();
+    let params: Vec<Blake2bParams<'_>> = keys
+        .iter()
+        .enumerate()
+        .map(|(j, key)| {
+            *Blake2bParams::new()
+                .key(key)
+                .digest_length(64 - j)
+                .last_node(j % 4 == 1)
+        })
+        .collect();
+
+    for count in 0..=inputs.len() {
+        let mut outputs = vec![Default::default(); count];
+        Blake2b::hash_many(&params[..count], &inputs[..count], &mut outputs).unwrap();
+        for ((out, msg), p) in outputs.iter().zip(inputs.iter()).zip(params.iter()) {
+            let mut hasher = p.build().unwrap();
+            let n = hasher.output_size();
+            hasher.update(msg);
+            hasher.finalize_variable(|res| assert_eq!(out[..n], *res));
+            assert!(out[n..].iter().all(|&b| b == 0));
+        }
+    }
+}
+
+#[test]
+fn blake2s_hash_many() {
+    let msgs = messages();
+    let keys = keys();
+    let inputs: Vec<&[u8]> = msgs.iter().map(|m| &m[..]).collect();
+    let params: Vec<Blake2sParams<'_>> = keys
+        .iter()
+        .enumerate()
+        .map(|(j, key)| {
+            *Blake2sParams::new()
+                .key(&key[..key.len().min(32)])
+                .digest_length(32 - j)
+                .last_node(j % 4 == 1)
+        })
+        .collect();
+
+    for count in 0..=inputs.len() {
+        let mut outputs = vec![Default::default(); count];
+        Blake2s::hash_many(&params[..count], &inputs[..count], &mut outputs).unwrap();
+        for ((out, msg), p) in outputs.iter().zip(inputs.iter()).zip(params.iter()) {
+            let mut hasher = p.build().unwrap();
+            let n = hasher.output_size();
+            hasher.update(msg);
+            hasher.finalize_variable(|res| assert_eq!(out[..n], *res));
+            assert!(out[n..].iter().all(|&b| b == 0));
+        }
+    }
+}
+
+#[test]
+fn hash_many_invalid_params() {
+    let params = [Blake2sParams::new(), *Blake2sParams::new().digest_length(0)];
+    let mut outputs = [Default::default(); 2];
+    assert_eq!(
+        Blake2s::hash_many(&params, &[b"a", b"b"], &mut outputs),
+        Err(ParamsError::DigestLength)
+    );
+    assert_eq!(outputs, [Default::default(); 2]);
+}
+
+#[test]
+#[should_panic]
+fn hash_many_length_mismatch() {
+    let mut outputs = vec![Default::default(); 1];
+    Blake2b::hash_many(&[Blake2bParams::new(); 2], &[b"a", b"b"], &mut outputs).unwrap();
+}
@@ -0,0 +1,96 @@
+use blake2::digest::{Update, VariableOutput};
+use blake2::{Blake2bParams, Blake2bp, Blake2sParams, Blake2sp, Digest};
+
+fn finalize<D: VariableOutput>(hasher: D) -> Vec<u8> {
+    let mut out = Vec::new();
+    hasher.finalize_variable(|res| out.extend_from_slice(res));
+    out
+}
+
+/// BLAKE2bp computed sequentially from its leaves and root node.
+fn blake2bp_tree(data: &[u8]) -> Vec<u8> {
+    let mut root = Blake2bParams::new()
+        .fanout(4)
+        .max_depth(2)
+        .node_depth(1)
+        .inner_length(64)
+        .last_node(true)
+        .build()
+        .unwrap();
+    for i in 0..4 {
+        let mut leaf = Blake2bParams::new()
+            .fanout(4)
+            .max_depth(2)
+            .node_offset(i as u64)
+            .inner_length(64)
+            .last_node(i == 3)
+            .build()
+            .unwrap();
+        for block in data.chunks(128).skip(i).step_by(4) {
+            leaf.update(block);
+        }
+        root.update(finalize(leaf));
+    }
+    finalize(root)
+}
+
+/// BLAKE2sp computed sequentially from its leaves and root node.
+fn blake2sp_tree(data: &[u8]) -> Vec<u8> {
+    let mut root = Blake2sParams::new()
+        .fanout(8)
+        .max_depth(2)
+        .node_depth(1)
+        .inner_length(32)
+        .last_node(true)
+        .build()
+        .unwrap();
+    for i in 0..8 {
+        let mut leaf = Blake2sParams::new()
+            .fanout(8)
+            .max_depth(2)
+            .node_offset(i as u64)
+            .inner_length(32)
+            .last_node(i == 7)
+            .build()
+            .unwrap();
+        for block in data.chunks(64).skip(i).step_by(8) {
+            leaf.update(block);
+        }
+        root.update(finalize(leaf));
+    }
+    finalize(root)
+}
+
+/// Hashing a large input at once, in small pieces and in two uneven parts
+/// must match the sequential tree. With the `rayon` feature these cover
+/// parallel updates of large inputs, of buffered small inputs, and the
+/// parallel finalization.
+fn check_large_input<D: Digest>(tree: fn(&[u8]) -> Vec<u8>) {
+    for &len in &[(1 << 20) + 1234, 70_000, 5000, 300] {
+        let data: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+        let expected = tree(&data);
+
+        assert_eq!(D::digest(&data)[..], expected[..]);
+
+        let mut hasher = D::new();
+        for chunk in data.chunks(1000) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize()[..], expected[..]);
+
+        let mut hasher = D::new();
+        hasher.update(&data[..100]);
+        hasher.update(&data[100..]);
+        assert_eq!(hasher.finalize()[..], expected[..]);
+    }
+}
+
+#[test]
+fn blake2bp_large_input() {
+    check_large_input::<Blake2bp>(blake2bp_tree);
+}
+
+#[test]
+fn blake2sp_large_input() {
+    check_large_input::<Blake2sp>(blake2sp_tree);
+}
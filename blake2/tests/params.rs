@@ -0,0 +1,176 @@
+use blake2::digest::{Update, VariableOutput};
+use blake2::{
+    Blake2b, Blake2bParams, Blake2bp, Blake2sParams, Blake2sp, Digest, ParamsError, VarBlake2b,
+};
+use hex_literal::hex;
+
+fn finalize<D: VariableOutput>(hasher: D) -> Vec<u8> {
+    let mut out = Vec::new();
+    hasher.finalize_variable(|res| out.extend_from_slice(res));
+    out
+}
+
+#[test]
+fn defaults() {
+    let mut hasher = Blake2bParams::new().build().unwrap();
+    hasher.update(b"hello world");
+    assert_eq!(finalize(hasher), Blake2b::digest(b"hello world")[..]);
+
+    let key = [0x42; 32];
+    let mut hasher = Blake2bParams::new()
+        .digest_length(20)
+        .key(&key)
+        .salt(b"salt")
+        .persona(b"persona")
+        .build()
+        .unwrap();
+    hasher.update(b"hello world");
+    let mut expected = VarBlake2b::with_params(&key, b"salt", b"persona", 20);
+    expected.update(b"hello world");
+    assert_eq!(finalize(hasher), finalize(expected));
+}
+
+#[test]
+fn all_fields() {
+    let mut hasher = Blake2bParams::new()
+        .digest_length(32)
+        .key(b"key")
+        .salt(b"salt")
+        .persona(b"persona")
+        .fanout(2)
+        .max_depth(3)
+        .leaf_length(4096)
+        .node_offset(5)
+        .node_depth(1)
+        .inner_length(32)
+        .last_node(true)
+        .build()
+        .unwrap();
+    hasher.update(b"hello world");
+    assert_eq!(
+        finalize(hasher),
+        hex!("a989b664aba54bcf1bd1c22f1a686b8be26189cfb02977ad09aaf31054afbb27")
+    );
+
+    let mut hasher = Blake2sParams::new()
+        .digest_length(20)
+        .key(b"key")
+        .salt(b"salt")
+        .persona(b"persona")
+        .fanout(2)
+        .max_depth(3)
+        .leaf_length(4096)
+        .node_offset((1 << 48) - 2)
+        .node_depth(1)
+        .inner_length(32)
+        .last_node(true)
+        .build()
+        .unwrap();
+    hasher.update(b"hello world");
+    assert_eq!(
+        finalize(hasher),
+        hex!("f0db9d1f3418657be7b942fb0b613aa0188d9a0b")
+    );
+}
+
+/// Build the unkeyed parallel modes from their nodes.
+#[test]
+fn tree() {
+    let data: Vec<u8> = (0..3000).map(|i| i as u8).collect();
+
+    let mut root = Blake2bParams::new()
+        .fanout(4)
+        .max_depth(2)
+        .node_depth(1)
+        .inner_length(64)
+        .last_node(true)
+        .build()
+        .unwrap();
+    for i in 0..4 {
+        let mut leaf = Blake2bParams::new()
+            .fanout(4)
+            .max_depth(2)
+            .node_offset(i as u64)
+            .inner_length(64)
+            .last_node(i == 3)
+            .build()
+            .unwrap();
+        for block in data.chunks(128).skip(i).step_by(4) {
+            leaf.update(block);
+        }
+        root.update(finalize(leaf));
+    }
+    assert_eq!(finalize(root), Blake2bp::digest(&data)[..]);
+
+    let mut root = Blake2sParams::new()
+        .fanout(8)
+        .max_depth(2)
+        .node_depth(1)
+        .inner_length(32)
+        .last_node(true)
+        .build()
+        .unwrap();
+    for i in 0..8 {
+        let mut leaf = Blake2sParams::new()
+            .fanout(8)
+            .max_depth(2)
+            .node_offset(i as u64)
+            .inner_length(32)
+            .last_node(i == 7)
+            .build()
+            .unwrap();
+        for block in data.chunks(64).skip(i).step_by(8) {
+            leaf.update(block);
+        }
+        root.update(finalize(leaf));
+    }
+    assert_eq!(finalize(root), Blake2sp::digest(&data)[..]);
+}
+
+#[test]
+fn invalid() {
+    let long = [0u8; 65];
+    let err = |params: &Blake2bParams<'_>| params.build().unwrap_err();
+    assert_eq!(
+        err(Blake2bParams::new().digest_length(0)),
+        ParamsError::DigestLength
+    );
+    assert_eq!(
+        err(Blake2bParams::new().digest_length(65)),
+        ParamsError::DigestLength
+    );
+    assert_eq!(err(Blake2bParams::new().key(&long)), ParamsError::KeyLength);
+    assert_eq!(
+        err(Blake2bParams::new().salt(&long[..17])),
+        ParamsError::SaltLength
+    );
+    assert_eq!(
+        err(Blake2bParams::new().persona(&long[..17])),
+        ParamsError::PersonaLength
+    );
+    assert_eq!(
+        err(Blake2bParams::new().max_depth(0)),
+        ParamsError::MaxDepth
+    );
+    assert_eq!(
+        err(Blake2bParams::new().inner_length(65)),
+        ParamsError::InnerLength
+    );
+    assert!(Blake2bParams::new().node_offset(!0).build().is_ok());
+
+    assert_eq!(
+        Blake2sParams::new().key(&long[..33]).build().unwrap_err(),
+        ParamsError::KeyLength
+    );
+    assert_eq!(
+        Blake2sParams::new().salt(&long[..9]).build().unwrap_err(),
+        ParamsError::SaltLength
+    );
+    assert_eq!(
+        Blake2sParams::new()
+            .node_offset(1 << 48)
+            .build()
+            .unwrap_err(),
+        ParamsError::NodeOffset
+    );
+}
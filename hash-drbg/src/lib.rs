@@ -0,0 +1,282 @@
+//! An implementation of the Hash_DRBG deterministic random bit generator
+//! specified in [NIST SP 800-90A Rev. 1][1], section 10.1.1.
+//!
+//! [`HashDrbg`] is generic over the hash function. Instantiations for
+//! SHA-256, SHA-512 (requires the `sha2` feature) and SHA3-256 (requires the
+//! `sha3` feature) are provided as type aliases.
+//!
+//! The DRBG does not gather entropy itself: the entropy input and nonce are
+//! supplied by the caller on instantiation, as well as on reseeding and on
+//! requests with prediction resistance.
+//!
+//! # Usage
+//!
+//! ```rust
+//! # #[cfg(feature = "sha2")]
+//! # {
+//! use hash_drbg::HashDrbgSha256;
+//!
+//! let entropy = [0x42; 32];
+//! let nonce = [0x24; 16];
+//! let mut drbg = HashDrbgSha256::new(&entropy, &nonce, b"my application").unwrap();
+//!
+//! let mut buf = [0u8; 64];
+//! drbg.generate(&mut buf, &[]).unwrap();
+//!
+//! // mix fresh entropy into the state
+//! let entropy = [0x17; 32];
+//! drbg.reseed(&entropy, &[]).unwrap();
+//! drbg.generate(&mut buf, b"additional input").unwrap();
+//! # }
+//! ```
+//!
+//! [1]: https://csrc.nist.gov/publications/detail/sp/800-90a/rev-1/final
+
+#![no_std]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg",
+    html_favicon_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg"
+)]
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+#[cfg(feature = "std")]
+extern crate std;
+
+pub use digest::{self, Digest};
+
+use core::{fmt, marker::PhantomData};
+use digest::generic_array::typenum::Unsigned;
+use digest::BlockInput;
+
+/// Maximum length of `V` and `C` in bytes, i.e. 888 bits.
+const MAX_SEED_LEN: usize = 111;
+
+/// Maximum number of requests between reseeds (SP 800-90A, table 2).
+pub const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// Maximum number of bytes per request, i.e. 2^19 bits.
+pub const MAX_REQUEST_LEN: usize = 1 << 16;
+
+/// Hash_DRBG with SHA-256.
+#[cfg(feature = "sha2")]
+pub type HashDrbgSha256 = HashDrbg<sha2::Sha256>;
+/// Hash_DRBG with SHA-512.
+#[cfg(feature = "sha2")]
+pub type HashDrbgSha512 = HashDrbg<sha2::Sha512>;
+/// Hash_DRBG with SHA3-256.
+#[cfg(feature = "sha3")]
+pub type HashDrbgSha3_256 = HashDrbg<sha3::Sha3_256>;
+
+/// The error type for DRBG operations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The entropy input is shorter than the security strength.
+    InsufficientEntropy,
+    /// More than [`MAX_REQUEST_LEN`] bytes were requested at once.
+    RequestTooLarge,
+    /// The reseed interval is exhausted, [`HashDrbg::reseed`] must be called
+    /// before generating more output.
+    ReseedRequired,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Error::InsufficientEntropy => "entropy input is too short",
+            Error::RequestTooLarge => "requested too many bytes",
+            Error::ReseedRequired => "reseed required",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Hash_DRBG over the hash function `D`.
+///
+/// The seed length and security strength are those of SP 800-90A, table 2,
+/// for the output size of `D`: a 440-bit seed for outputs up to 256 bits and
+/// an 888-bit seed for larger outputs.
+#[derive(Clone)]
+pub struct HashDrbg<D: Digest + BlockInput> {
+    v: [u8; MAX_SEED_LEN],
+    c: [u8; MAX_SEED_LEN],
+    reseed_counter: u64,
+    reseed_interval: u64,
+    hash: PhantomData<D>,
+}
+
+impl<D: Digest + BlockInput> HashDrbg<D> {
+    /// Length of `V` and `C` in bytes.
+    fn seed_len() -> usize {
+        if D::OutputSize::USIZE <= 32 {
+            55
+        } else {
+            111
+        }
+    }
+
+    /// Security strength in bytes.
+    fn security_strength() -> usize {
+        match D::OutputSize::USIZE {
+            0..=20 => 16,
+            21..=28 => 24,
+            _ => 32,
+        }
+    }
+
+    /// Instantiate the DRBG from the entropy input, a nonce and an optional
+    /// personalization string.
+    ///
+    /// Returns an error if `entropy_input` is shorter than the security
+    /// strength (e.g. 32 bytes for SHA-256).
+    pub fn new(entropy_input: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Self, Error> {
+        if entropy_input.len() < Self::security_strength() {
+            return Err(Error::InsufficientEntropy);
+        }
+        let mut drbg = HashDrbg {
+            v: [0; MAX_SEED_LEN],
+            c: [0; MAX_SEED_LEN],
+            reseed_counter: 1,
+            reseed_interval: MAX_RESEED_INTERVAL,
+            hash: PhantomData,
+        };
+        let n = Self::seed_len();
+        hash_df::<D>(&[entropy_input, nonce, personalization], &mut drbg.v[..n]);
+        drbg.update_c();
+        Ok(drbg)
+    }
+
+    /// Reseed the DRBG with fresh entropy input and optional additional
+    /// input.
+    ///
+    /// Returns an error if `entropy_input` is shorter than the security
+    /// strength.
+    pub fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) -> Result<(), Error> {
+        if entropy_input.len() < Self::security_strength() {
+            return Err(Error::InsufficientEntropy);
+        }
+        let n = Self::seed_len();
+        let mut seed = [0u8; MAX_SEED_LEN];
+        hash_df::<D>(
+            &[&[0x01], &self.v[..n], entropy_input, additional_input],
+            &mut seed[..n],
+        );
+        self.v = seed;
+        self.update_c();
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    /// Fill `out` with pseudorandom bytes, mixing in the optional additional
+    /// input.
+    ///
+    /// Returns an error if more than [`MAX_REQUEST_LEN`] bytes are requested
+    /// or if the DRBG must be reseeded first.
+    pub fn generate(&mut self, out: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        if out.len() > MAX_REQUEST_LEN {
+            return Err(Error::RequestTooLarge);
+        }
+        if self.reseed_counter > self.reseed_interval {
+            return Err(Error::ReseedRequired);
+        }
+
+        let n = Self::seed_len();
+        if !additional_input.is_empty() {
+            let w = hash::<D>(&[&[0x02], &self.v[..n], additional_input]);
+            add(&mut self.v[..n], &w);
+        }
+
+        self.hashgen(out);
+
+        let h = hash::<D>(&[&[0x03], &self.v[..n]]);
+        let c = self.c;
+        add(&mut self.v[..n], &h);
+        add(&mut self.v[..n], &c[..n]);
+        add(&mut self.v[..n], &self.reseed_counter.to_be_bytes());
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    /// Fill `out` with pseudorandom bytes with prediction resistance, i.e.
+    /// reseed with `entropy_input` and `additional_input` before generating.
+    pub fn generate_with_prediction_resistance(
+        &mut self,
+        out: &mut [u8],
+        entropy_input: &[u8],
+        additional_input: &[u8],
+    ) -> Result<(), Error> {
+        if out.len() > MAX_REQUEST_LEN {
+            return Err(Error::RequestTooLarge);
+        }
+        self.reseed(entropy_input, additional_input)?;
+        self.generate(out, &[])
+    }
+
+    /// Set the number of requests allowed between reseeds, at most
+    /// [`MAX_RESEED_INTERVAL`].
+    pub fn set_reseed_interval(&mut self, interval: u64) {
+        self.reseed_interval = interval.min(MAX_RESEED_INTERVAL);
+    }
+
+    /// Returns `true` if the DRBG must be reseeded before generating more
+    /// output.
+    pub fn needs_reseed(&self) -> bool {
+        self.reseed_counter > self.reseed_interval
+    }
+
+    fn update_c(&mut self) {
+        let n = Self::seed_len();
+        hash_df::<D>(&[&[0x00], &self.v[..n]], &mut self.c[..n]);
+    }
+
+    fn hashgen(&self, out: &mut [u8]) {
+        let n = Self::seed_len();
+        let mut data = self.v;
+        for chunk in out.chunks_mut(D::OutputSize::USIZE) {
+            let w = hash::<D>(&[&data[..n]]);
+            chunk.copy_from_slice(&w[..chunk.len()]);
+            add(&mut data[..n], &[1]);
+        }
+    }
+}
+
+impl<D: Digest + BlockInput> fmt::Debug for HashDrbg<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HashDrbg { ... }")
+    }
+}
+
+fn hash<D: Digest>(parts: &[&[u8]]) -> digest::Output<D> {
+    let mut d = D::new();
+    for part in parts {
+        d.update(part);
+    }
+    d.finalize()
+}
+
+/// The Hash_df derivation function (SP 800-90A, section 10.3.1).
+fn hash_df<D: Digest>(parts: &[&[u8]], out: &mut [u8]) {
+    let bits = (out.len() as u32) << 3;
+    for (i, chunk) in out.chunks_mut(D::OutputSize::USIZE).enumerate() {
+        let mut d = D::new();
+        d.update([(i + 1) as u8]);
+        d.update(bits.to_be_bytes());
+        for part in parts {
+            d.update(part);
+        }
+        chunk.copy_from_slice(&d.finalize()[..chunk.len()]);
+    }
+}
+
+/// Add the big-endian number `x` to `v` modulo `2^(8 * v.len())`.
+fn add(v: &mut [u8], x: &[u8]) {
+    let mut carry = 0u16;
+    let mut x = x.iter().rev();
+    for b in v.iter_mut().rev() {
+        let s = u16::from(*b) + u16::from(*x.next().unwrap_or(&0)) + carry;
+        *b = s as u8;
+        carry = s >> 8;
+    }
+}
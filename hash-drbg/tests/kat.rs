@@ -0,0 +1,133 @@
+//! Known-answer tests in the layout of the NIST CAVP response files, see
+//! the headers of the files in `tests/data` for their sources.
+#![cfg(all(feature = "sha2", feature = "sha3"))]
+use hash_drbg::{Digest, HashDrbg, HashDrbgSha256, HashDrbgSha3_256, HashDrbgSha512};
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Runs a single test case with the fields in file order.
+fn run<D: Digest + hash_drbg::digest::BlockInput>(fields: &[(&str, Vec<u8>)]) {
+    let get = |key: &str| -> Vec<&[u8]> {
+        fields
+            .iter()
+            .filter(|(k, _)| *k == key)
+            .map(|(_, v)| &v[..])
+            .collect()
+    };
+    let entropy = get("EntropyInput")[0];
+    let nonce = get("Nonce")[0];
+    let pers = get("PersonalizationString")[0];
+    let additional = get("AdditionalInput");
+    let expected = get("ReturnedBits")[0];
+
+    let mut drbg = HashDrbg::<D>::new(entropy, nonce, pers).unwrap();
+    let mut out = vec![0u8; expected.len()];
+
+    let entropy_pr = get("EntropyInputPR");
+    if entropy_pr.is_empty() {
+        if let Some(entropy) = get("EntropyInputReseed").first() {
+            let additional = get("AdditionalInputReseed")[0];
+            drbg.reseed(entropy, additional).unwrap();
+        }
+        drbg.generate(&mut out, additional[0]).unwrap();
+        drbg.generate(&mut out, additional[1]).unwrap();
+    } else {
+        for i in 0..2 {
+            drbg.generate_with_prediction_resistance(&mut out, entropy_pr[i], additional[i])
+                .unwrap();
+        }
+    }
+    assert_eq!(out, expected);
+}
+
+fn run_file(data: &str) -> usize {
+    let mut count = 0;
+    let mut alg = "";
+    let mut fields = Vec::new();
+    for line in data.lines().map(str::trim) {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            if !line.contains('=') {
+                alg = line.trim_matches(|c| c == '[' || c == ']');
+            }
+            continue;
+        }
+
+        let mut kv = line.splitn(2, '=').map(str::trim);
+        let key = kv.next().unwrap();
+        if key == "COUNT" {
+            fields.clear();
+            continue;
+        }
+        fields.push((key, decode(kv.next().unwrap_or(""))));
+
+        if key == "ReturnedBits" {
+            match alg {
+                "SHA-256" => run::<sha2::Sha256>(&fields),
+                "SHA-512" => run::<sha2::Sha512>(&fields),
+                "SHA3-256" => run::<sha3::Sha3_256>(&fields),
+                _ => continue,
+            }
+            count += 1;
+        }
+    }
+    count
+}
+
+#[test]
+fn nist_no_reseed() {
+    let count = run_file(include_str!("data/nist_no_reseed.rsp"));
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn openssl_sha2_pr_false() {
+    let count = run_file(include_str!("data/openssl_sha2_pr_false.rsp"));
+    assert_eq!(count, 8);
+}
+
+#[test]
+fn openssl_sha2_pr_true() {
+    let count = run_file(include_str!("data/openssl_sha2_pr_true.rsp"));
+    assert_eq!(count, 8);
+}
+
+#[test]
+fn openssl_sha3_pr_false() {
+    let count = run_file(include_str!("data/openssl_sha3_pr_false.rsp"));
+    assert_eq!(count, 4);
+}
+
+#[test]
+fn openssl_sha3_pr_true() {
+    let count = run_file(include_str!("data/openssl_sha3_pr_true.rsp"));
+    assert_eq!(count, 4);
+}
+
+#[test]
+fn aliases() {
+    let entropy = [0x42; 32];
+    let nonce = [0x24; 16];
+    let mut out = [[0u8; 64]; 3];
+    HashDrbgSha256::new(&entropy, &nonce, &[])
+        .unwrap()
+        .generate(&mut out[0], &[])
+        .unwrap();
+    HashDrbgSha512::new(&entropy, &nonce, &[])
+        .unwrap()
+        .generate(&mut out[1], &[])
+        .unwrap();
+    HashDrbgSha3_256::new(&entropy, &nonce, &[])
+        .unwrap()
+        .generate(&mut out[2], &[])
+        .unwrap();
+    assert_ne!(out[0][..], out[1][..]);
+    assert_ne!(out[0][..], out[2][..]);
+}
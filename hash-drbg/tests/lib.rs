@@ -0,0 +1,34 @@
+#![cfg(feature = "sha2")]
+use hash_drbg::{Error, HashDrbgSha256, MAX_REQUEST_LEN};
+
+#[test]
+fn insufficient_entropy() {
+    let err = HashDrbgSha256::new(&[0; 31], &[0; 16], &[]).unwrap_err();
+    assert_eq!(err, Error::InsufficientEntropy);
+
+    let mut drbg = HashDrbgSha256::new(&[0; 32], &[0; 16], &[]).unwrap();
+    assert_eq!(drbg.reseed(&[0; 16], &[]), Err(Error::InsufficientEntropy));
+}
+
+#[test]
+fn request_too_large() {
+    let mut drbg = HashDrbgSha256::new(&[0; 32], &[0; 16], &[]).unwrap();
+    let mut buf = vec![0u8; MAX_REQUEST_LEN + 1];
+    assert_eq!(drbg.generate(&mut buf, &[]), Err(Error::RequestTooLarge));
+    assert!(drbg.generate(&mut buf[..MAX_REQUEST_LEN], &[]).is_ok());
+}
+
+#[test]
+fn reseed_interval() {
+    let mut drbg = HashDrbgSha256::new(&[0; 32], &[0; 16], &[]).unwrap();
+    drbg.set_reseed_interval(2);
+    let mut buf = [0u8; 32];
+    drbg.generate(&mut buf, &[]).unwrap();
+    drbg.generate(&mut buf, &[]).unwrap();
+    assert!(drbg.needs_reseed());
+    assert_eq!(drbg.generate(&mut buf, &[]), Err(Error::ReseedRequired));
+
+    drbg.reseed(&[1; 32], &[]).unwrap();
+    assert!(!drbg.needs_reseed());
+    drbg.generate(&mut buf, &[]).unwrap();
+}
@@ -3,6 +3,9 @@
 //!
 //! <https://github.com/gvanas/KeccakCodePackage/blob/master/Standalone/kangaroo_twelve-reference/K12.py>
 //!
+//! The [`TurboShake128`] and [`TurboShake256`] extendable-output functions
+//! which KangarooTwelve is built upon are also exposed directly.
+//!
 //! Some optimisations copied from: <https://github.com/RustCrypto/hashes/tree/master/sha3/src>
 
 // Based off this translation originally by Diggory Hardy:
@@ -16,6 +19,9 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 // TODO(tarcieri): eliminate alloc requirement
 #[macro_use]
 extern crate alloc;
@@ -24,10 +30,15 @@ pub use digest;
 
 #[macro_use]
 mod lanes;
+mod turbo_shake;
+
+pub use crate::turbo_shake::{
+    InvalidDomainSeparation, TurboShake128, TurboShake128Reader, TurboShake256, TurboShake256Reader,
+};
 
 // TODO(tarcieri): eliminate usage of `Vec`
 use alloc::vec::Vec;
-use core::{cmp::min, convert::TryInto, mem};
+use core::{cmp::min, mem};
 use digest::{ExtendableOutputDirty, Reset, Update, XofReader};
 
 /// The KangarooTwelve extendable-output function (XOF).
@@ -166,73 +177,15 @@ impl XofReader for Reader {
     }
 }
 
-fn f(input: &[u8], suffix: u8, mut output_len: usize) -> Vec<u8> {
-    let mut state = [0u8; 200];
-    let max_block_size = 1344 / 8; // r, also known as rate in bytes
-
-    // === Absorb all the input blocks ===
-    // We unroll first loop, which allows simple copy
-    let mut block_size = min(input.len(), max_block_size);
-    state[0..block_size].copy_from_slice(&input[0..block_size]);
-
-    let mut offset = block_size;
-    while offset < input.len() {
-        keccak(&mut state);
-        block_size = min(input.len() - offset, max_block_size);
-        for i in 0..block_size {
-            // TODO: is this sufficiently optimisable or better to convert to u64 first?
-            state[i] ^= input[i + offset];
-        }
-        offset += block_size;
-    }
-    if block_size == max_block_size {
-        // TODO: condition is nearly always false; tests pass without this.
-        // Why is it here?
-        keccak(&mut state);
-        block_size = 0;
-    }
-
-    // === Do the padding and switch to the squeezing phase ===
-    state[block_size] ^= suffix;
-    if ((suffix & 0x80) != 0) && (block_size == (max_block_size - 1)) {
-        // TODO: condition is almost always false — in fact tests pass without
-        // this block! So why is it here?
-        keccak(&mut state);
-    }
-    state[max_block_size - 1] ^= 0x80;
-    keccak(&mut state);
-
-    // === Squeeze out all the output blocks ===
-    let mut output = Vec::with_capacity(output_len);
-    while output_len > 0 {
-        block_size = min(output_len, max_block_size);
-        output.extend_from_slice(&state[0..block_size]);
-        output_len -= block_size;
-        if output_len > 0 {
-            keccak(&mut state);
-        }
-    }
+fn f(input: &[u8], suffix: u8, output_len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; output_len];
+    // the suffixes used by KangarooTwelve are all valid domain separation bytes
+    let mut hasher = TurboShake128::new(suffix).unwrap();
+    hasher.update(input);
+    hasher.finalize_xof_dirty().read(&mut output);
     output
 }
 
-fn keccak(state: &mut [u8; 200]) {
-    let mut lanes = [0u64; 25];
-    let mut y;
-    for x in 0..5 {
-        FOR5!(y, 5, {
-            let pos = 8 * (x + y);
-            lanes[x + y] = u64::from_le_bytes(state[pos..(pos + 8)].try_into().unwrap());
-        });
-    }
-    lanes::keccak(&mut lanes);
-    for x in 0..5 {
-        FOR5!(y, 5, {
-            let i = 8 * (x + y);
-            state[i..i + 8].copy_from_slice(&lanes[x + y].to_le_bytes());
-        });
-    }
-}
-
 fn right_encode(mut x: usize) -> Vec<u8> {
     let mut slice = Vec::new();
     while x > 0 {
@@ -0,0 +1,175 @@
+//! TurboSHAKE extendable-output functions, i.e. SHAKE with the number of
+//! Keccak-p[1600] rounds reduced to 12.
+//!
+//! Based on [RFC 9861](https://www.rfc-editor.org/rfc/rfc9861).
+//!
+//! These live here rather than in `sha3` as they share the 12-round
+//! permutation with KangarooTwelve, while the `keccak` crate used by `sha3`
+//! only provides the full 24-round Keccak-f[1600].
+
+use crate::lanes;
+use core::{convert::TryInto, fmt};
+use digest::{ExtendableOutputDirty, Reset, Update, XofReader};
+
+/// Domain separation byte used by default, as recommended by the RFC.
+const DEFAULT_DOMAIN_SEPARATION: u8 = 0x1F;
+
+/// The error type returned for a domain separation byte outside of the
+/// `0x01..=0x7F` range.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct InvalidDomainSeparation;
+
+impl fmt::Display for InvalidDomainSeparation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("domain separation byte must be in the 0x01..=0x7F range")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidDomainSeparation {}
+
+#[derive(Clone, Debug)]
+struct Sponge {
+    lanes: [u64; 25],
+    rate: usize,
+    pos: usize,
+}
+
+impl Sponge {
+    fn new(rate: usize) -> Self {
+        Self {
+            lanes: [0; 25],
+            rate,
+            pos: 0,
+        }
+    }
+
+    fn absorb(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            let (lane, offset) = (self.pos / 8, self.pos % 8);
+            if offset == 0 && input.len() >= 8 {
+                let (word, rest) = input.split_at(8);
+                self.lanes[lane] ^= u64::from_le_bytes(word.try_into().unwrap());
+                self.pos += 8;
+                input = rest;
+            } else {
+                self.xor_byte(input[0]);
+                self.pos += 1;
+                input = &input[1..];
+            }
+
+            if self.pos == self.rate {
+                lanes::keccak(&mut self.lanes);
+                self.pos = 0;
+            }
+        }
+    }
+
+    fn xor_byte(&mut self, byte: u8) {
+        self.lanes[self.pos / 8] ^= (byte as u64) << (8 * (self.pos % 8));
+    }
+
+    fn pad(&mut self, domain_separation: u8) {
+        self.xor_byte(domain_separation);
+        self.pos = self.rate - 1;
+        self.xor_byte(0x80);
+        lanes::keccak(&mut self.lanes);
+        self.pos = 0;
+    }
+
+    fn squeeze(&mut self, buffer: &mut [u8]) {
+        for b in buffer {
+            if self.pos == self.rate {
+                lanes::keccak(&mut self.lanes);
+                self.pos = 0;
+            }
+            *b = (self.lanes[self.pos / 8] >> (8 * (self.pos % 8))) as u8;
+            self.pos += 1;
+        }
+    }
+}
+
+macro_rules! turbo_shake_impl {
+    ($name:ident, $reader:ident, $rate:expr, $doc:expr, $reader_doc:expr) => {
+        #[doc = $doc]
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            sponge: Sponge,
+            domain_separation: u8,
+        }
+
+        impl $name {
+            /// Create a new instance with the given domain separation byte.
+            ///
+            /// Returns an error if `domain_separation` is not in the
+            /// `0x01..=0x7F` range.
+            pub fn new(domain_separation: u8) -> Result<Self, InvalidDomainSeparation> {
+                if !(0x01..=0x7F).contains(&domain_separation) {
+                    return Err(InvalidDomainSeparation);
+                }
+                Ok(Self {
+                    sponge: Sponge::new($rate),
+                    domain_separation,
+                })
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    sponge: Sponge::new($rate),
+                    domain_separation: DEFAULT_DOMAIN_SEPARATION,
+                }
+            }
+        }
+
+        impl Update for $name {
+            fn update(&mut self, bytes: impl AsRef<[u8]>) {
+                self.sponge.absorb(bytes.as_ref());
+            }
+        }
+
+        impl ExtendableOutputDirty for $name {
+            type Reader = $reader;
+
+            fn finalize_xof_dirty(&mut self) -> Self::Reader {
+                let mut sponge = self.sponge.clone();
+                sponge.pad(self.domain_separation);
+                $reader { sponge }
+            }
+        }
+
+        impl Reset for $name {
+            fn reset(&mut self) {
+                self.sponge = Sponge::new($rate);
+            }
+        }
+
+        #[doc = $reader_doc]
+        #[derive(Clone, Debug)]
+        pub struct $reader {
+            sponge: Sponge,
+        }
+
+        impl XofReader for $reader {
+            fn read(&mut self, buffer: &mut [u8]) {
+                self.sponge.squeeze(buffer);
+            }
+        }
+    };
+}
+
+turbo_shake_impl!(
+    TurboShake128,
+    TurboShake128Reader,
+    168,
+    "The TurboSHAKE128 extendable-output function (XOF).",
+    "Extensible output reader for [`TurboShake128`]."
+);
+turbo_shake_impl!(
+    TurboShake256,
+    TurboShake256Reader,
+    136,
+    "The TurboSHAKE256 extendable-output function (XOF).",
+    "Extensible output reader for [`TurboShake256`]."
+);
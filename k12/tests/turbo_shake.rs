@@ -0,0 +1,158 @@
+use hex_literal::hex;
+use k12::{
+    digest::{ExtendableOutput, Update},
+    InvalidDomainSeparation, TurboShake128, TurboShake256,
+};
+
+// Test vectors from RFC 9861, section 5.
+
+/// Pattern `00 01 02 .. F9 FA` repeated, truncated to `len` bytes.
+fn ptn(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 0xFB) as u8).collect()
+}
+
+#[test]
+fn turbo_shake128() {
+    assert_eq!(
+        TurboShake128::default().finalize_boxed(64)[..],
+        hex!(
+            "
+            1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c
+            3e8ccae2a4dae56c84a04c2385c03c15e8193bdf58737363321691c05462c8df
+        "
+        )[..]
+    );
+    assert_eq!(
+        TurboShake128::default().finalize_boxed(10032)[10000..],
+        hex!("a3b9b0385900ce761f22aed548e754da10a5242d62e8c658e3f3a923a7555607")[..]
+    );
+    assert_eq!(
+        TurboShake128::default().chain(ptn(1)).finalize_boxed(32)[..],
+        hex!("55cedd6f60af7bb29a4042ae832ef3f58db7299f893ebb9247247d856958daa9")[..]
+    );
+    assert_eq!(
+        TurboShake128::default()
+            .chain(ptn(17 * 17))
+            .finalize_boxed(32)[..],
+        hex!("96c77c279e0126f7fc07c9b07f5cdae1e0be60bdbe10620040e75d7223a624d2")[..]
+    );
+    assert_eq!(
+        TurboShake128::default()
+            .chain(ptn(17 * 17 * 17))
+            .finalize_boxed(32)[..],
+        hex!("d4976eb56bcf118520582b709f73e1d6853e001fdaf80e1b13e0d0599d5fb372")[..]
+    );
+}
+
+#[test]
+fn turbo_shake128_domain_separation() {
+    let vectors: [(&[u8], u8, [u8; 32]); 6] = [
+        (
+            &hex!("ffffff"),
+            0x01,
+            hex!("bf323f940494e88ee1c540fe660be8a0c93f43d15ec006998462fa994eed5dab"),
+        ),
+        (
+            &hex!("ff"),
+            0x06,
+            hex!("8ec9c66465ed0d4a6c35d13506718d687a25cb05c74cca1e42501abd83874a67"),
+        ),
+        (
+            &hex!("ffffff"),
+            0x07,
+            hex!("b658576001cad9b1e5f399a9f77723bba05458042d68206f7252682dba3663ed"),
+        ),
+        (
+            &hex!("ffffffffffffff"),
+            0x0B,
+            hex!("8deeaa1aec47ccee569f659c21dfa8e112db3cee37b18178b2acd805b799cc37"),
+        ),
+        (
+            &hex!("ff"),
+            0x30,
+            hex!("553122e2135e363c3292bed2c6421fa232bab03daa07c7d6636603286506325b"),
+        ),
+        (
+            &hex!("ffffff"),
+            0x7F,
+            hex!("16274cc656d44cefd422395d0f9053bda6d28e122aba15c765e5ad0e6eaf26f9"),
+        ),
+    ];
+    for (msg, d, expected) in vectors.iter() {
+        let res = TurboShake128::new(*d)
+            .unwrap()
+            .chain(msg)
+            .finalize_boxed(32);
+        assert_eq!(res[..], expected[..]);
+    }
+}
+
+#[test]
+fn turbo_shake256() {
+    assert_eq!(
+        TurboShake256::default().finalize_boxed(64)[..],
+        hex!(
+            "
+            367a329dafea871c7802ec67f905ae13c57695dc2c6663c61035f59a18f8e7db
+            11edc0e12e91ea60eb6b32df06dd7f002fbafabb6e13ec1cc20d995547600db0
+        "
+        )[..]
+    );
+    assert_eq!(
+        TurboShake256::default().finalize_boxed(10032)[10000..],
+        hex!("abefa11630c661269249742685ec082f207265dccf2f43534e9c61ba0c9d1d75")[..]
+    );
+    assert_eq!(
+        TurboShake256::default().chain(ptn(1)).finalize_boxed(64)[..],
+        hex!(
+            "
+            3e1712f928f8eaf1054632b2aa0a246ed8b0c378728f60bc970410155c28820e
+            90cc90d8a3006aa2372c5c5ea176b0682bf22bae7467ac94f74d43d39b0482e2
+        "
+        )[..]
+    );
+    assert_eq!(
+        TurboShake256::default()
+            .chain(ptn(17 * 17))
+            .finalize_boxed(64)[..],
+        hex!(
+            "
+            66b810db8e90780424c0847372fdc95710882fde31c6df75beb9d4cd9305cfca
+            e35e7b83e8b7e6eb4b78605880116316fe2c078a09b94ad7b8213c0a738b65c0
+        "
+        )[..]
+    );
+    assert_eq!(
+        TurboShake256::new(0x7F)
+            .unwrap()
+            .chain(hex!("ffffff"))
+            .finalize_boxed(64)[..],
+        hex!(
+            "
+            abe569c1f77ec340f02705e7d37c9ab7e155516e4a6a150021d70b6fac0bb40c
+            069f9a9828a0d575cd99f9bae435ab1acf7ed9110ba97ce0388d074bac768776
+        "
+        )[..]
+    );
+}
+
+#[test]
+fn turbo_shake_incremental() {
+    let msg = ptn(17 * 17 * 17);
+    let mut hasher = TurboShake128::default();
+    for chunk in msg.chunks(13) {
+        hasher.update(chunk);
+    }
+    assert_eq!(
+        hasher.finalize_boxed(32)[..],
+        hex!("d4976eb56bcf118520582b709f73e1d6853e001fdaf80e1b13e0d0599d5fb372")[..]
+    );
+}
+
+#[test]
+fn turbo_shake_invalid_domain_separation() {
+    for &d in [0x00, 0x80, 0xFF].iter() {
+        assert_eq!(TurboShake128::new(d).err(), Some(InvalidDomainSeparation));
+        assert_eq!(TurboShake256::new(d).err(), Some(InvalidDomainSeparation));
+    }
+}
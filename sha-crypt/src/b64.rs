@@ -0,0 +1,44 @@
+//! The base64 variant used by `crypt(3)`.
+//!
+//! Unlike RFC 4648 it uses the alphabet `./0-9A-Za-z`, emits the least
+//! significant six bits of each group first and encodes the digest bytes in
+//! a shuffled order.
+
+const ALPHABET: &[u8; 64] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Byte order of the SHA-256-crypt digest, in groups of three bytes with the
+/// most significant byte first.
+pub(crate) const MAP_SHA256: [u8; 32] = [
+    0, 10, 20, 21, 1, 11, 12, 22, 2, 3, 13, 23, 24, 4, 14, 15, 25, 5, 6, 16, 26, 27, 7, 17, 18, 28,
+    8, 9, 19, 29, 31, 30,
+];
+
+/// Byte order of the SHA-512-crypt digest.
+pub(crate) const MAP_SHA512: [u8; 64] = [
+    0, 21, 42, 22, 43, 1, 44, 2, 23, 3, 24, 45, 25, 46, 4, 47, 5, 26, 6, 27, 48, 28, 49, 7, 50, 8,
+    29, 9, 30, 51, 31, 52, 10, 53, 11, 32, 12, 33, 54, 34, 55, 13, 56, 14, 35, 15, 36, 57, 37, 58,
+    16, 59, 17, 38, 18, 39, 60, 40, 61, 19, 62, 20, 41, 63,
+];
+
+/// Encode `digest` in the order given by `map` into `out`, returning the
+/// number of characters written.
+pub(crate) fn encode(digest: &[u8], map: &[u8], out: &mut [u8]) -> usize {
+    let mut pos = 0;
+    for group in map.chunks(3) {
+        let mut w = 0u32;
+        for &i in group {
+            w = (w << 8) | u32::from(digest[i as usize]);
+        }
+        for _ in 0..=group.len() {
+            out[pos] = ALPHABET[(w & 0x3f) as usize];
+            w >>= 6;
+            pos += 1;
+        }
+    }
+    pos
+}
+
+/// Returns `true` if `c` is part of the alphabet.
+pub(crate) fn is_valid(c: u8) -> bool {
+    ALPHABET.contains(&c)
+}
@@ -0,0 +1,456 @@
+//! An implementation of the SHA-256-crypt and SHA-512-crypt password hashing
+//! schemes as specified by [Ulrich Drepper][1], i.e. the `$5$` and `$6$`
+//! formats of `crypt(3)` found in `/etc/shadow`.
+//!
+//! # Usage
+//!
+//! ```rust
+//! # #[cfg(feature = "alloc")]
+//! # {
+//! use sha_crypt::{sha512_check, sha512_crypt_b64, Params};
+//!
+//! let params = Params::new(10_000);
+//! let hash = sha512_crypt_b64(b"password", "saltsaltsaltsalt", &params).unwrap();
+//! assert!(hash.starts_with("$6$rounds=10000$saltsaltsaltsalt$"));
+//!
+//! assert!(sha512_check(b"password", &hash).is_ok());
+//! assert!(sha512_check(b"wrong password", &hash).is_err());
+//! # }
+//! ```
+//!
+//! Existing hashes can be inspected with [`PasswordHash::parse`], and
+//! [`check`] verifies a password against a hash of either format. The
+//! comparison of the computed and the stored hash runs in constant time.
+//!
+//! [1]: https://www.akkadia.org/drepper/SHA-crypt.txt
+
+#![no_std]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg",
+    html_favicon_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg"
+)]
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod b64;
+
+pub use sha2;
+
+use core::fmt;
+use digest::Digest;
+use sha2::{Sha256, Sha512};
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use core::fmt::Write;
+
+/// Number of rounds used if none are specified.
+pub const ROUNDS_DEFAULT: u32 = 5_000;
+/// Minimum number of rounds, smaller values are raised to it.
+pub const ROUNDS_MIN: u32 = 1_000;
+/// Maximum number of rounds, larger values are lowered to it.
+pub const ROUNDS_MAX: u32 = 999_999_999;
+/// Maximum salt length in bytes, longer salts are truncated.
+pub const SALT_MAX_LEN: usize = 16;
+
+/// Length of the longest encoded hash, i.e. of SHA-512-crypt.
+const HASH_MAX_LEN: usize = 86;
+
+/// The error type for parsing and verifying password hashes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The string is not a `$5$` or `$6$` hash, or the hash part is
+    /// malformed.
+    InvalidFormat,
+    /// The `rounds=` parameter is not a decimal number.
+    InvalidRounds,
+    /// The salt contains a `$`, a `:` or a non-printable character.
+    InvalidSalt,
+    /// The password does not match the hash.
+    HashMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Error::InvalidFormat => "invalid password hash format",
+            Error::InvalidRounds => "invalid rounds parameter",
+            Error::InvalidSalt => "invalid salt",
+            Error::HashMismatch => "password does not match the hash",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Hash algorithm of a password hash.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Algorithm {
+    /// SHA-256-crypt, identified by `$5$`.
+    Sha256,
+    /// SHA-512-crypt, identified by `$6$`.
+    Sha512,
+}
+
+impl Algorithm {
+    fn prefix(self) -> &'static str {
+        match self {
+            Algorithm::Sha256 => "$5$",
+            Algorithm::Sha512 => "$6$",
+        }
+    }
+
+    fn hash_len(self) -> usize {
+        match self {
+            Algorithm::Sha256 => 43,
+            Algorithm::Sha512 => HASH_MAX_LEN,
+        }
+    }
+
+    /// Compute the encoded hash into `buf` and return it.
+    fn crypt_b64<'b>(
+        self,
+        password: &[u8],
+        salt: &[u8],
+        params: &Params,
+        buf: &'b mut [u8; HASH_MAX_LEN],
+    ) -> &'b [u8] {
+        let n = match self {
+            Algorithm::Sha256 => {
+                let digest = sha_crypt::<Sha256>(password, salt, params.rounds);
+                b64::encode(&digest, &b64::MAP_SHA256, buf)
+            }
+            Algorithm::Sha512 => {
+                let digest = sha_crypt::<Sha512>(password, salt, params.rounds);
+                b64::encode(&digest, &b64::MAP_SHA512, buf)
+            }
+        };
+        &buf[..n]
+    }
+}
+
+/// Hashing parameters, i.e. the number of rounds.
+///
+/// The number of rounds is clamped to [`ROUNDS_MIN`]..=[`ROUNDS_MAX`] as
+/// required by the specification. A hash string contains the `rounds=`
+/// parameter only if the rounds were given explicitly, even if they are
+/// equal to [`ROUNDS_DEFAULT`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Params {
+    rounds: u32,
+    explicit: bool,
+}
+
+impl Params {
+    /// Create parameters with an explicit number of rounds.
+    pub fn new(rounds: u32) -> Self {
+        Params {
+            rounds: rounds.max(ROUNDS_MIN).min(ROUNDS_MAX),
+            explicit: true,
+        }
+    }
+
+    /// Returns the number of rounds.
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+}
+
+impl Default for Params {
+    /// Parameters with [`ROUNDS_DEFAULT`] rounds, which are omitted from hash
+    /// strings.
+    fn default() -> Self {
+        Params {
+            rounds: ROUNDS_DEFAULT,
+            explicit: false,
+        }
+    }
+}
+
+impl fmt::Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.explicit {
+            write!(f, "rounds={}$", self.rounds)?;
+        }
+        Ok(())
+    }
+}
+
+/// A parsed `$5$` or `$6$` password hash.
+///
+/// The `Display` implementation writes the hash in the modular crypt format.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PasswordHash<'a> {
+    algorithm: Algorithm,
+    params: Params,
+    salt: &'a str,
+    hash: &'a str,
+}
+
+impl<'a> PasswordHash<'a> {
+    /// Parse a password hash of the form `$5$rounds=<N>$<salt>$<hash>`,
+    /// where the `rounds=` parameter is optional.
+    ///
+    /// The number of rounds is clamped and the salt truncated to
+    /// [`SALT_MAX_LEN`] bytes like `crypt(3)` does.
+    pub fn parse(s: &'a str) -> Result<Self, Error> {
+        let algorithm = match s.get(..3) {
+            Some("$5$") => Algorithm::Sha256,
+            Some("$6$") => Algorithm::Sha512,
+            _ => return Err(Error::InvalidFormat),
+        };
+        let rest = &s[3..];
+
+        let (params, rest) = if rest.get(..7) == Some("rounds=") {
+            let rest = &rest[7..];
+            let end = rest.find('$').ok_or(Error::InvalidFormat)?;
+            (parse_rounds(&rest[..end])?, &rest[end + 1..])
+        } else {
+            (Params::default(), rest)
+        };
+
+        let end = rest.find('$').ok_or(Error::InvalidFormat)?;
+        let salt = check_salt(&rest[..end])?;
+        let hash = &rest[end + 1..];
+        if hash.len() != algorithm.hash_len() || !hash.bytes().all(b64::is_valid) {
+            return Err(Error::InvalidFormat);
+        }
+
+        Ok(PasswordHash {
+            algorithm,
+            params,
+            salt,
+            hash,
+        })
+    }
+
+    /// Returns the hash algorithm.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Returns the hashing parameters.
+    pub fn params(&self) -> Params {
+        self.params
+    }
+
+    /// Returns the salt.
+    pub fn salt(&self) -> &'a str {
+        self.salt
+    }
+
+    /// Returns the encoded hash.
+    pub fn hash(&self) -> &'a str {
+        self.hash
+    }
+
+    /// Verify `password` against the hash in constant time.
+    pub fn verify(&self, password: &[u8]) -> Result<(), Error> {
+        let mut buf = [0u8; HASH_MAX_LEN];
+        let computed =
+            self.algorithm
+                .crypt_b64(password, self.salt.as_bytes(), &self.params, &mut buf);
+        if computed.ct_eq(self.hash.as_bytes()).into() {
+            Ok(())
+        } else {
+            Err(Error::HashMismatch)
+        }
+    }
+}
+
+impl fmt::Display for PasswordHash<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}${}",
+            self.algorithm.prefix(),
+            self.params,
+            self.salt,
+            self.hash
+        )
+    }
+}
+
+fn parse_rounds(s: &str) -> Result<Params, Error> {
+    if s.is_empty() {
+        return Err(Error::InvalidRounds);
+    }
+    let mut rounds = 0u64;
+    for c in s.bytes() {
+        if !c.is_ascii_digit() {
+            return Err(Error::InvalidRounds);
+        }
+        rounds = (10 * rounds + u64::from(c - b'0')).min(u64::from(ROUNDS_MAX));
+    }
+    Ok(Params::new(rounds as u32))
+}
+
+/// Validate a salt for use in a hash string and truncate it.
+fn check_salt(salt: &str) -> Result<&str, Error> {
+    if !salt
+        .bytes()
+        .all(|c| c.is_ascii_graphic() && c != b'$' && c != b':')
+    {
+        return Err(Error::InvalidSalt);
+    }
+    Ok(&salt[..salt.len().min(SALT_MAX_LEN)])
+}
+
+/// The SHA-crypt algorithm over the hash function `D`.
+fn sha_crypt<D: Digest>(password: &[u8], salt: &[u8], rounds: u32) -> digest::Output<D> {
+    let salt = &salt[..salt.len().min(SALT_MAX_LEN)];
+
+    let b = D::new()
+        .chain(password)
+        .chain(salt)
+        .chain(password)
+        .finalize();
+
+    let mut a = D::new().chain(password).chain(salt);
+    update_repeated(&mut a, &b, password.len());
+    let mut len = password.len();
+    while len > 0 {
+        if len & 1 == 1 {
+            a.update(&b);
+        } else {
+            a.update(password);
+        }
+        len >>= 1;
+    }
+    let a = a.finalize();
+
+    let mut dp = D::new();
+    for _ in 0..password.len() {
+        dp.update(password);
+    }
+    let dp = dp.finalize();
+
+    let mut ds = D::new();
+    for _ in 0..16 + usize::from(a[0]) {
+        ds.update(salt);
+    }
+    let ds = ds.finalize();
+    // the salt is never longer than the digest
+    let s = &ds[..salt.len()];
+
+    let mut c = a;
+    for i in 0..rounds {
+        let (rem3, rem7) = (i % 3, i % 7);
+        let mut d = D::new();
+        if i & 1 == 1 {
+            update_repeated(&mut d, &dp, password.len());
+        } else {
+            d.update(&c);
+        }
+        if rem3 != 0 {
+            d.update(s);
+        }
+        if rem7 != 0 {
+            update_repeated(&mut d, &dp, password.len());
+        }
+        if i & 1 == 1 {
+            d.update(&c);
+        } else {
+            update_repeated(&mut d, &dp, password.len());
+        }
+        c = d.finalize();
+    }
+    c
+}
+
+/// Feed `len` bytes of `block` repeated to `d`.
+fn update_repeated<D: Digest>(d: &mut D, block: &[u8], len: usize) {
+    let mut rem = len;
+    while rem > block.len() {
+        d.update(block);
+        rem -= block.len();
+    }
+    d.update(&block[..rem]);
+}
+
+/// Compute the raw SHA-256-crypt digest.
+///
+/// The salt is truncated to [`SALT_MAX_LEN`] bytes.
+pub fn sha256_crypt(password: &[u8], salt: &[u8], params: &Params) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&sha_crypt::<Sha256>(password, salt, params.rounds));
+    out
+}
+
+/// Compute the raw SHA-512-crypt digest.
+///
+/// The salt is truncated to [`SALT_MAX_LEN`] bytes.
+pub fn sha512_crypt(password: &[u8], salt: &[u8], params: &Params) -> [u8; 64] {
+    let mut out = [0u8; 64];
+    out.copy_from_slice(&sha_crypt::<Sha512>(password, salt, params.rounds));
+    out
+}
+
+#[cfg(feature = "alloc")]
+fn crypt_string(
+    algorithm: Algorithm,
+    password: &[u8],
+    salt: &str,
+    params: &Params,
+) -> Result<String, Error> {
+    let salt = check_salt(salt)?;
+    let mut buf = [0u8; HASH_MAX_LEN];
+    let hash = algorithm.crypt_b64(password, salt.as_bytes(), params, &mut buf);
+
+    let mut out = String::with_capacity(32 + hash.len());
+    out.push_str(algorithm.prefix());
+    write!(out, "{}", params).map_err(|_| Error::InvalidFormat)?;
+    out.push_str(salt);
+    out.push('$');
+    out.extend(hash.iter().map(|&c| char::from(c)));
+    Ok(out)
+}
+
+/// Compute a SHA-256-crypt hash string, e.g. `$5$rounds=10000$salt$...`.
+///
+/// The salt is truncated to [`SALT_MAX_LEN`] bytes. Returns an error if it
+/// contains a `$`, a `:` or a non-printable character.
+#[cfg(feature = "alloc")]
+pub fn sha256_crypt_b64(password: &[u8], salt: &str, params: &Params) -> Result<String, Error> {
+    crypt_string(Algorithm::Sha256, password, salt, params)
+}
+
+/// Compute a SHA-512-crypt hash string, e.g. `$6$rounds=10000$salt$...`.
+///
+/// The salt is truncated to [`SALT_MAX_LEN`] bytes. Returns an error if it
+/// contains a `$`, a `:` or a non-printable character.
+#[cfg(feature = "alloc")]
+pub fn sha512_crypt_b64(password: &[u8], salt: &str, params: &Params) -> Result<String, Error> {
+    crypt_string(Algorithm::Sha512, password, salt, params)
+}
+
+/// Verify `password` against a `$5$` or `$6$` hash string.
+pub fn check(password: &[u8], hashed: &str) -> Result<(), Error> {
+    PasswordHash::parse(hashed)?.verify(password)
+}
+
+/// Verify `password` against a `$5$` hash string.
+pub fn sha256_check(password: &[u8], hashed: &str) -> Result<(), Error> {
+    check_algorithm(Algorithm::Sha256, password, hashed)
+}
+
+/// Verify `password` against a `$6$` hash string.
+pub fn sha512_check(password: &[u8], hashed: &str) -> Result<(), Error> {
+    check_algorithm(Algorithm::Sha512, password, hashed)
+}
+
+fn check_algorithm(algorithm: Algorithm, password: &[u8], hashed: &str) -> Result<(), Error> {
+    let hash = PasswordHash::parse(hashed)?;
+    if hash.algorithm != algorithm {
+        return Err(Error::InvalidFormat);
+    }
+    hash.verify(password)
+}
@@ -0,0 +1,232 @@
+use sha_crypt::{
+    check, sha256_check, sha512_check, Algorithm, Error, Params, PasswordHash, ROUNDS_MAX,
+    ROUNDS_MIN,
+};
+
+/// Test vectors from the specification: password, setting, hash string.
+const SHA256_TESTS: &[(&str, &str, &str)] = &[
+    (
+        "Hello world!",
+        "saltstring",
+        "$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5",
+    ),
+    (
+        "Hello world!",
+        "rounds=10000$saltstringsaltstring",
+        "$5$rounds=10000$saltstringsaltst$3xv.VbSHBb41AL9AvLeujZkZRBAwqFMz2.opqey6IcA",
+    ),
+    (
+        "This is just a test",
+        "rounds=5000$toolongsaltstring",
+        "$5$rounds=5000$toolongsaltstrin$Un/5jzAHMgOGZ5.mWJpuVolil07guHPvOW8mGRcvxa5",
+    ),
+    (
+        "a very much longer text to encrypt.  This one even stretches over morethan one line.",
+        "rounds=1400$anotherlongsaltstring",
+        "$5$rounds=1400$anotherlongsalts$Rx.j8H.h8HjEDGomFU8bDkXm3XIUnzyxf12oP84Bnq1",
+    ),
+    (
+        "we have a short salt string but not a short password",
+        "rounds=77777$short",
+        "$5$rounds=77777$short$JiO1O3ZpDAxGJeaDIuqCoEFysAe1mZNJRs3pw0KQRd/",
+    ),
+    (
+        "a short string",
+        "rounds=123456$asaltof16chars..",
+        "$5$rounds=123456$asaltof16chars..$gP3VQ/6X7UUEW3HkBn2w1/Ptq2jxPyzV/cZKmF/wJvD",
+    ),
+    (
+        "the minimum number is still observed",
+        "rounds=10$roundstoolow",
+        "$5$rounds=1000$roundstoolow$yfvwcWrQ8l/K0DAWyuPMDNHpIVlTQebY9l/gL972bIC",
+    ),
+];
+
+const SHA512_TESTS: &[(&str, &str, &str)] = &[
+    (
+        "Hello world!",
+        "saltstring",
+        "$6$saltstring$svn8UoSVapNtMuq1ukKS4tPQd8iKwSMHWjl/O817G3uBnIFNjnQJuesI68u4OTLiBFdcbYEdFCoEOfaS35inz1",
+    ),
+    (
+        "Hello world!",
+        "rounds=10000$saltstringsaltstring",
+        "$6$rounds=10000$saltstringsaltst$OW1/O6BYHV6BcXZu8QVeXbDWra3Oeqh0sbHbbMCVNSnCM/UrjmM0Dp8vOuZeHBy/YTBmSK6H9qs/y3RnOaw5v.",
+    ),
+    (
+        "This is just a test",
+        "rounds=5000$toolongsaltstring",
+        "$6$rounds=5000$toolongsaltstrin$lQ8jolhgVRVhY4b5pZKaysCLi0QBxGoNeKQzQ3glMhwllF7oGDZxUhx1yxdYcz/e1JSbq3y6JMxxl8audkUEm0",
+    ),
+    (
+        "a very much longer text to encrypt.  This one even stretches over morethan one line.",
+        "rounds=1400$anotherlongsaltstring",
+        "$6$rounds=1400$anotherlongsalts$POfYwTEok97VWcjxIiSOjiykti.o/pQs.wPvMxQ6Fm7I6IoYN3CmLs66x9t0oSwbtEW7o7UmJEiDwGqd8p4ur1",
+    ),
+    (
+        "we have a short salt string but not a short password",
+        "rounds=77777$short",
+        "$6$rounds=77777$short$WuQyW2YR.hBNpjjRhpYD/ifIw05xdfeEyQoMxIXbkvr0gge1a1x3yRULJ5CCaUeOxFmtlcGZelFl5CxtgfiAc0",
+    ),
+    (
+        "a short string",
+        "rounds=123456$asaltof16chars..",
+        "$6$rounds=123456$asaltof16chars..$BtCwjqMJGx5hrJhZywWvt0RLE8uZ4oPwcelCjmw2kSYu.Ec6ycULevoBK25fs2xXgMNrCzIMVcgEJAstJeonj1",
+    ),
+    (
+        "the minimum number is still observed",
+        "rounds=10$roundstoolow",
+        "$6$rounds=1000$roundstoolow$kUMsbe306n21p9R.FRkW3IGn.S9NPN0x50YhH1xhLsPuWGsUSklZt58jaTfF4ZEQpyUNGc0dqbpBYYBaHHrsX.",
+    ),
+];
+
+/// Split a setting into parameters and salt.
+#[cfg(feature = "alloc")]
+fn setting(s: &str) -> (Params, &str) {
+    match s.find('$') {
+        Some(i) => {
+            let rounds = s["rounds=".len()..i].parse().unwrap();
+            (Params::new(rounds), &s[i + 1..])
+        }
+        None => (Params::default(), s),
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn sha256_crypt_b64() {
+    for &(password, setting_str, expected) in SHA256_TESTS {
+        let (params, salt) = setting(setting_str);
+        let hash = sha_crypt::sha256_crypt_b64(password.as_bytes(), salt, &params).unwrap();
+        assert_eq!(hash, expected);
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn sha512_crypt_b64() {
+    for &(password, setting_str, expected) in SHA512_TESTS {
+        let (params, salt) = setting(setting_str);
+        let hash = sha_crypt::sha512_crypt_b64(password.as_bytes(), salt, &params).unwrap();
+        assert_eq!(hash, expected);
+    }
+}
+
+#[test]
+fn verify() {
+    for &(password, _, hash) in SHA256_TESTS {
+        assert_eq!(sha256_check(password.as_bytes(), hash), Ok(()));
+        assert_eq!(check(password.as_bytes(), hash), Ok(()));
+        assert_eq!(check(b"wrong", hash), Err(Error::HashMismatch));
+        assert_eq!(
+            sha512_check(password.as_bytes(), hash),
+            Err(Error::InvalidFormat)
+        );
+    }
+    for &(password, _, hash) in SHA512_TESTS {
+        assert_eq!(sha512_check(password.as_bytes(), hash), Ok(()));
+        assert_eq!(check(password.as_bytes(), hash), Ok(()));
+        assert_eq!(check(b"wrong", hash), Err(Error::HashMismatch));
+        assert_eq!(
+            sha256_check(password.as_bytes(), hash),
+            Err(Error::InvalidFormat)
+        );
+    }
+}
+
+#[test]
+fn raw_digest() {
+    let params = Params::new(1000);
+    let long = sha_crypt::sha512_crypt(b"password", b"toolongsaltstring", &params);
+    let short = sha_crypt::sha512_crypt(b"password", b"toolongsaltstrin", &params);
+    assert_eq!(long[..], short[..]);
+
+    let clamped = sha_crypt::sha512_crypt(b"password", b"toolongsaltstrin", &Params::new(10));
+    assert_eq!(clamped[..], short[..]);
+
+    let default = sha_crypt::sha512_crypt(b"password", b"toolongsaltstrin", &Params::default());
+    assert_ne!(default[..], short[..]);
+    assert_ne!(
+        sha_crypt::sha256_crypt(b"password", b"salt", &params),
+        sha_crypt::sha256_crypt(b"passwore", b"salt", &params),
+    );
+}
+
+#[test]
+fn parse() {
+    let hash = PasswordHash::parse(SHA256_TESTS[1].2).unwrap();
+    assert_eq!(hash.algorithm(), Algorithm::Sha256);
+    assert_eq!(hash.params(), Params::new(10_000));
+    assert_eq!(hash.salt(), "saltstringsaltst");
+    assert_eq!(hash.hash(), "3xv.VbSHBb41AL9AvLeujZkZRBAwqFMz2.opqey6IcA");
+    assert_eq!(hash.to_string(), SHA256_TESTS[1].2);
+
+    let hash = PasswordHash::parse(SHA512_TESTS[0].2).unwrap();
+    assert_eq!(hash.algorithm(), Algorithm::Sha512);
+    assert_eq!(hash.params(), Params::default());
+    assert_eq!(hash.params().rounds(), 5000);
+    assert_eq!(hash.to_string(), SHA512_TESTS[0].2);
+
+    // salt and rounds are normalized like crypt(3) does
+    let hash = PasswordHash::parse(
+        "$5$rounds=10$roundstoolowroundstoolow$yfvwcWrQ8l/K0DAWyuPMDNHpIVlTQebY9l/gL972bIC",
+    )
+    .unwrap();
+    assert_eq!(hash.params().rounds(), ROUNDS_MIN);
+    assert_eq!(hash.salt(), "roundstoolowroun");
+}
+
+#[test]
+fn parse_errors() {
+    let hash = "5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5";
+    let invalid = [
+        (format!("$1$saltstring${}", hash), Error::InvalidFormat),
+        (format!("$5$saltstring{}", hash), Error::InvalidFormat),
+        (format!("$5$saltstring${}x", hash), Error::InvalidFormat),
+        (
+            format!("$5$saltstring${}", &hash[1..]),
+            Error::InvalidFormat,
+        ),
+        (
+            format!("$5$saltstring$_{}", &hash[1..]),
+            Error::InvalidFormat,
+        ),
+        (
+            format!("$5$rounds=$saltstring${}", hash),
+            Error::InvalidRounds,
+        ),
+        (
+            format!("$5$rounds=-5$saltstring${}", hash),
+            Error::InvalidRounds,
+        ),
+        (format!("$5$salt:string${}", hash), Error::InvalidSalt),
+        (format!("$5$salt string${}", hash), Error::InvalidSalt),
+    ];
+    for (s, err) in invalid.iter() {
+        assert_eq!(PasswordHash::parse(s), Err(*err), "{}", s);
+    }
+}
+
+#[test]
+fn rounds_clamping() {
+    assert_eq!(Params::new(0).rounds(), ROUNDS_MIN);
+    assert_eq!(Params::new(999).rounds(), ROUNDS_MIN);
+    assert_eq!(Params::new(1000).rounds(), 1000);
+    assert_eq!(Params::new(!0).rounds(), ROUNDS_MAX);
+
+    let hash = "$5$rounds=99999999999999999999$salt$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5";
+    let hash = PasswordHash::parse(hash).unwrap();
+    assert_eq!(hash.params().rounds(), ROUNDS_MAX);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn invalid_salt() {
+    let params = Params::default();
+    for &salt in ["a$b", "a:b", "a\nb", "\u{e9}"].iter() {
+        assert_eq!(
+            sha_crypt::sha256_crypt_b64(b"password", salt, &params),
+            Err(Error::InvalidSalt)
+        );
+    }
+}
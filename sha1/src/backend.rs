@@ -0,0 +1,37 @@
+//! Introspection and runtime selection of the compression backends.
+//!
+//! By default the fastest backend supported by the CPU is selected at
+//! runtime. A different backend can be forced, e.g. to compare performance
+//! or to pin the software implementation while debugging:
+//!
+//! ```
+//! use sha1::backend::{self, Backend};
+//!
+//! backend::force_sha1(Some(Backend::Soft)).unwrap();
+//! assert_eq!(backend::sha1(), Backend::Soft);
+//!
+//! // return to automatic selection
+//! backend::force_sha1(None).unwrap();
+//! ```
+//!
+//! The selection is global to the process and takes effect on the next call
+//! of the compression function, including for hashers which are already in
+//! use. All backends produce identical results.
+pub(crate) mod shared;
+
+pub(crate) use self::shared::Override;
+pub use self::shared::{Backend, UnsupportedBackend};
+
+/// Returns the backend used for SHA-1.
+pub fn sha1() -> Backend {
+    crate::compress::backend()
+}
+
+/// Force the backend used for SHA-1, or return to automatic selection with
+/// `None`.
+///
+/// Returns an error if the backend is not available in this build or on
+/// this CPU, in which case the current selection is kept.
+pub fn force_sha1(backend: Option<Backend>) -> Result<(), UnsupportedBackend> {
+    crate::compress::force_backend(backend)
+}
@@ -0,0 +1,109 @@
+// This file is shared by the sha1 and sha2 crates and must be identical in
+// both, which is checked by the tests of sha2.
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Compression backend.
+///
+/// Not every backend exists for every hash function, forcing one which does
+/// not returns [`UnsupportedBackend`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Portable software implementation.
+    Soft,
+    /// x86 SHA extensions (SHA-NI).
+    ShaNi,
+    /// x86 AVX2 message schedule with scalar rounds.
+    Avx2,
+    /// x86 SSSE3 message schedule with scalar rounds.
+    Ssse3,
+    /// Assembly implementation from the `sha1-asm` or `sha2-asm` crate.
+    Asm,
+    /// ARMv8 cryptography extensions.
+    Aarch64,
+}
+
+impl Backend {
+    fn to_u8(self) -> u8 {
+        match self {
+            Backend::Soft => 1,
+            Backend::ShaNi => 2,
+            Backend::Avx2 => 3,
+            Backend::Ssse3 => 4,
+            Backend::Asm => 5,
+            Backend::Aarch64 => 6,
+        }
+    }
+
+    fn from_u8(v: u8) -> Option<Backend> {
+        match v {
+            1 => Some(Backend::Soft),
+            2 => Some(Backend::ShaNi),
+            3 => Some(Backend::Avx2),
+            4 => Some(Backend::Ssse3),
+            5 => Some(Backend::Asm),
+            6 => Some(Backend::Aarch64),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Backend::Soft => "soft",
+            Backend::ShaNi => "sha-ni",
+            Backend::Avx2 => "avx2",
+            Backend::Ssse3 => "ssse3",
+            Backend::Asm => "asm",
+            Backend::Aarch64 => "aarch64",
+        })
+    }
+}
+
+/// The error type returned when forcing a backend which is not compiled in
+/// or not supported by the CPU.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UnsupportedBackend;
+
+impl fmt::Display for UnsupportedBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("backend is not available")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnsupportedBackend {}
+
+/// Backend forced at runtime, if any.
+pub(crate) struct Override(AtomicU8);
+
+impl Override {
+    pub(crate) const fn new() -> Self {
+        Override(AtomicU8::new(0))
+    }
+
+    pub(crate) fn get(&self) -> Option<Backend> {
+        Backend::from_u8(self.0.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn set(&self, backend: Option<Backend>) {
+        self.0
+            .store(backend.map_or(0, Backend::to_u8), Ordering::Relaxed);
+    }
+}
+
+/// Detection for builds which contain only the software implementation.
+#[allow(dead_code)]
+pub(crate) mod soft_only {
+    use super::Backend;
+
+    pub fn detect() -> Backend {
+        Backend::Soft
+    }
+
+    pub fn is_supported(backend: Backend) -> bool {
+        backend == Backend::Soft
+    }
+}
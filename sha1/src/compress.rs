@@ -1,3 +1,4 @@
+use crate::backend::{Backend, Override, UnsupportedBackend};
 use digest::consts::U64;
 use digest::generic_array::GenericArray;
 
@@ -5,12 +6,26 @@ cfg_if::cfg_if! {
     if #[cfg(feature = "force-soft")] {
         mod soft;
         use soft::compress as compress_inner;
+        use crate::backend::shared::soft_only::{detect, is_supported};
     } else if #[cfg(all(feature = "asm", target_arch = "aarch64", target_os = "linux"))] {
         mod soft;
         mod aarch64;
-        use aarch64::compress as compress_inner;
+        use aarch64::{compress as compress_inner, detect, is_supported};
     } else if #[cfg(all(feature = "asm", any(target_arch = "x86", target_arch = "x86_64")))] {
+        mod soft;
+
+        fn detect() -> Backend {
+            Backend::Asm
+        }
+
+        fn is_supported(backend: Backend) -> bool {
+            backend == Backend::Asm || backend == Backend::Soft
+        }
+
         fn compress_inner(state: &mut [u32; 5], blocks: &[[u8; 64]]) {
+            if backend() == Backend::Soft {
+                return soft::compress(state, blocks);
+            }
             for block in blocks {
                 sha1_asm::compress(state, block);
             }
@@ -18,10 +33,28 @@ cfg_if::cfg_if! {
     } else if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
         mod soft;
         mod x86;
-        use x86::compress as compress_inner;
+        use x86::{compress as compress_inner, detect, is_supported};
     } else {
         mod soft;
         use soft::compress as compress_inner;
+        use crate::backend::shared::soft_only::{detect, is_supported};
+    }
+}
+
+static BACKEND: Override = Override::new();
+
+/// Returns the forced backend or the fastest one supported by the CPU.
+pub(crate) fn backend() -> Backend {
+    BACKEND.get().unwrap_or_else(detect)
+}
+
+pub(crate) fn force_backend(backend: Option<Backend>) -> Result<(), UnsupportedBackend> {
+    match backend {
+        Some(b) if !is_supported(b) => Err(UnsupportedBackend),
+        _ => {
+            BACKEND.set(backend);
+            Ok(())
+        }
     }
 }
 
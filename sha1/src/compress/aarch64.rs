@@ -1,4 +1,5 @@
 #![cfg(feature = "asm-aarch64")]
+use crate::backend::Backend;
 use libc::{getauxval, AT_HWCAP, HWCAP_SHA1};
 
 fn sha1_supported() -> bool {
@@ -7,11 +8,27 @@ fn sha1_supported() -> bool {
     (hwcaps & HWCAP_SHA1) != 0
 }
 
-pub fn compress(state: &mut [u32; 5], blocks: &[u8; 64]) {
+pub fn detect() -> Backend {
     // TODO: Replace this platform-specific call with is_aarch64_feature_detected!("sha1") once
     // that macro is stabilised and https://github.com/rust-lang/rfcs/pull/2725 is implemented
     // to let us use it on no_std.
     if sha1_supported() {
+        Backend::Aarch64
+    } else {
+        Backend::Soft
+    }
+}
+
+pub fn is_supported(backend: Backend) -> bool {
+    match backend {
+        Backend::Soft => true,
+        Backend::Aarch64 => sha1_supported(),
+        _ => false,
+    }
+}
+
+pub fn compress(state: &mut [u32; 5], blocks: &[[u8; 64]]) {
+    if super::backend() == Backend::Aarch64 {
         for block in blocks {
             sha1_asm::compress(state, block);
         }
@@ -1,6 +1,8 @@
 #![cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 #![allow(unsafe_code)]
 
+use crate::backend::Backend;
+
 #[cfg(target_arch = "x86")]
 use core::arch::x86::*;
 #[cfg(target_arch = "x86_64")]
@@ -95,10 +97,26 @@ unsafe fn digest_blocks(state: &mut [u32; 5], blocks: &[[u8; 64]]) {
     state[4] = _mm_extract_epi32(state_e, 3) as u32;
 }
 
-pub fn compress(state: &mut [u32; 5], blocks: &[[u8; 64]]) {
+pub fn detect() -> Backend {
     // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
     // after stabilization
     if cpuid_bool::cpuid_bool!("sha", "sse2", "ssse3", "sse4.1") {
+        Backend::ShaNi
+    } else {
+        Backend::Soft
+    }
+}
+
+pub fn is_supported(backend: Backend) -> bool {
+    match backend {
+        Backend::Soft => true,
+        Backend::ShaNi => cpuid_bool::cpuid_bool!("sha", "sse2", "ssse3", "sse4.1"),
+        _ => false,
+    }
+}
+
+pub fn compress(state: &mut [u32; 5], blocks: &[[u8; 64]]) {
+    if super::backend() == Backend::ShaNi {
         unsafe {
             digest_blocks(state, blocks);
         }
@@ -18,6 +18,9 @@
 //! assert_eq!(result[..], hex!("2aae6c35c94fcfb415dbe95f408b9ce91ee846ed"));
 //! ```
 //!
+//! The compression backend is selected at runtime and can be inspected or
+//! forced with the functions in the [`backend`] module.
+//!
 //! Also see [RustCrypto/hashes][2] readme.
 //!
 //! [1]: https://en.wikipedia.org/wiki/SHA-1
@@ -34,6 +37,7 @@
 #[cfg(feature = "std")]
 extern crate std;
 
+pub mod backend;
 mod compress;
 mod consts;
 
@@ -0,0 +1,42 @@
+use digest::Digest;
+use sha1::backend::{self, Backend, UnsupportedBackend};
+use sha1::Sha1;
+
+const BACKENDS: [Backend; 4] = [
+    Backend::Soft,
+    Backend::ShaNi,
+    Backend::Asm,
+    Backend::Aarch64,
+];
+
+// the selection is global, so all checks are done in a single test
+#[test]
+fn force_backend() {
+    let msg: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+    let expected = Sha1::digest(&msg);
+    let default = backend::sha1();
+
+    for &b in BACKENDS.iter() {
+        match backend::force_sha1(Some(b)) {
+            Ok(()) => {
+                assert_eq!(backend::sha1(), b);
+                assert_eq!(Sha1::digest(&msg), expected);
+            }
+            Err(UnsupportedBackend) => assert_eq!(backend::sha1(), default),
+        }
+        backend::force_sha1(None).unwrap();
+    }
+
+    assert_eq!(backend::sha1(), default);
+    // backends of SHA-2 which do not exist for SHA-1
+    assert_eq!(
+        backend::force_sha1(Some(Backend::Avx2)),
+        Err(UnsupportedBackend)
+    );
+    assert_eq!(
+        backend::force_sha1(Some(Backend::Ssse3)),
+        Err(UnsupportedBackend)
+    );
+    assert!(backend::force_sha1(Some(Backend::Soft)).is_ok());
+    backend::force_sha1(None).unwrap();
+}
@@ -0,0 +1,52 @@
+//! Introspection and runtime selection of the compression backends.
+//!
+//! By default the fastest backend supported by the CPU is selected at
+//! runtime. A different backend can be forced for SHA-224/256 and
+//! SHA-384/512 independently, e.g. to compare performance or to pin the
+//! software implementation while debugging:
+//!
+//! ```
+//! use sha2::backend::{self, Backend};
+//!
+//! backend::force_sha256(Some(Backend::Soft)).unwrap();
+//! assert_eq!(backend::sha256(), Backend::Soft);
+//!
+//! // return to automatic selection
+//! backend::force_sha256(None).unwrap();
+//! ```
+//!
+//! The selection is global to the process and takes effect on the next call
+//! of the compression function, including for hashers which are already in
+//! use. All backends produce identical results.
+pub(crate) mod shared;
+
+pub(crate) use self::shared::Override;
+pub use self::shared::{Backend, UnsupportedBackend};
+
+/// Returns the backend used for SHA-224 and SHA-256.
+pub fn sha256() -> Backend {
+    crate::sha256::backend()
+}
+
+/// Returns the backend used for SHA-384, SHA-512 and SHA-512/t.
+pub fn sha512() -> Backend {
+    crate::sha512::backend()
+}
+
+/// Force the backend used for SHA-224 and SHA-256, or return to automatic
+/// selection with `None`.
+///
+/// Returns an error if the backend is not available in this build or on
+/// this CPU, in which case the current selection is kept.
+pub fn force_sha256(backend: Option<Backend>) -> Result<(), UnsupportedBackend> {
+    crate::sha256::force_backend(backend)
+}
+
+/// Force the backend used for SHA-384, SHA-512 and SHA-512/t, or return to
+/// automatic selection with `None`.
+///
+/// Returns an error if the backend is not available in this build or on
+/// this CPU, in which case the current selection is kept.
+pub fn force_sha512(backend: Option<Backend>) -> Result<(), UnsupportedBackend> {
+    crate::sha512::force_backend(backend)
+}
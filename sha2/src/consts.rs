@@ -35,6 +35,19 @@ pub const K32X4: [[u32; 4]; 16] = [
     [K32[63], K32[62], K32[61], K32[60]],
 ];
 
+/// Message schedule of the padding block of a 64-byte message with the
+/// round constants added, i.e. `W[t] + K32[t]`.
+pub const PAD64_WK32: [u32; 64] = [
+    0xc28a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf374,
+    0x649b69c1, 0xf0fe4786, 0x0fe1edc6, 0x240cf254, 0x4fe9346f, 0x6cc984be, 0x61b9411e, 0x16f988fa,
+    0xf2c65152, 0xa88e5a6d, 0xb019fc65, 0xb9d99ec7, 0x9a1231c3, 0xe70eeaa0, 0xfdb1232b, 0xc7353eb0,
+    0x3069bad5, 0xcb976d5f, 0x5a0f118f, 0xdc1eeefd, 0x0a35b689, 0xde0b7a04, 0x58f4ca9d, 0xe15d5b16,
+    0x007f3e86, 0x37088980, 0xa507ea32, 0x6fab9537, 0x17406110, 0x0d8cd6f1, 0xcdaa3b6d, 0xc0bbbe37,
+    0x83613bda, 0xdb48a363, 0x0b02e931, 0x6fd15ca7, 0x521afaca, 0x31338431, 0x6ed41a95, 0x6d437890,
+    0xc39c91f2, 0x9eccabbd, 0xb5c9a0e6, 0x532fb63c, 0xd2c741c6, 0x07237ea3, 0xa4954b68, 0x4c191d76,
+];
+
 /// Constants necessary for SHA-512 family of digests.
 pub const K64: [u64; 80] = [
     0x428a2f98d728ae22,
@@ -163,15 +176,15 @@ pub const K64X2: [[u64; 2]; 40] = [
     [K64[79], K64[78]],
 ];
 
-pub static H224: [u32; STATE_LEN] = [
+pub const H224: [u32; STATE_LEN] = [
     0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
 ];
 
-pub static H256: [u32; STATE_LEN] = [
+pub const H256: [u32; STATE_LEN] = [
     0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
 ];
 
-pub static H384: [u64; STATE_LEN] = [
+pub const H384: [u64; STATE_LEN] = [
     0xcbbb9d5dc1059ed8,
     0x629a292a367cd507,
     0x9159015a3070dd17,
@@ -182,7 +195,7 @@ pub static H384: [u64; STATE_LEN] = [
     0x47b5481dbefa4fa4,
 ];
 
-pub static H512: [u64; STATE_LEN] = [
+pub const H512: [u64; STATE_LEN] = [
     0x6a09e667f3bcc908,
     0xbb67ae8584caa73b,
     0x3c6ef372fe94f82b,
@@ -193,7 +206,7 @@ pub static H512: [u64; STATE_LEN] = [
     0x5be0cd19137e2179,
 ];
 
-pub static H512_TRUNC_224: [u64; STATE_LEN] = [
+pub const H512_TRUNC_224: [u64; STATE_LEN] = [
     0x8c3d37c819544da2,
     0x73e1996689dcd4d6,
     0x1dfab7ae32ff9c82,
@@ -204,7 +217,7 @@ pub static H512_TRUNC_224: [u64; STATE_LEN] = [
     0x1112e6ad91d692a1,
 ];
 
-pub static H512_TRUNC_256: [u64; STATE_LEN] = [
+pub const H512_TRUNC_256: [u64; STATE_LEN] = [
     0x22312194fc2bf72c,
     0x9f555fa3c84c64c2,
     0x2393b86b6f53b151,
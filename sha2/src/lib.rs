@@ -3,15 +3,33 @@
 //! There are 6 standard algorithms specified in the SHA-2 standard:
 //!
 //! * `Sha224`, which is the 32-bit `Sha256` algorithm with the result truncated
-//! to 224 bits.
+//!   to 224 bits.
 //! * `Sha256`, which is the 32-bit `Sha256` algorithm.
 //! * `Sha384`, which is the 64-bit `Sha512` algorithm with the result truncated
-//! to 384 bits.
+//!   to 384 bits.
 //! * `Sha512`, which is the 64-bit `Sha512` algorithm.
 //! * `Sha512Trunc224`, which is the 64-bit `Sha512` algorithm with the result
-//! truncated to 224 bits.
+//!   truncated to 224 bits.
 //! * `Sha512Trunc256`, which is the 64-bit `Sha512` algorithm with the result
-//! truncated to 256 bits.
+//!   truncated to 256 bits.
+//!
+//! Additionally `Sha512Trunc` and `Sha512TruncFixed` implement SHA-512/t for
+//! an arbitrary output size `t`, generating the initial hash value on
+//! construction.
+//!
+//! `Sha256_192` is the `Sha256` algorithm with the result truncated to 192
+//! bits, as specified in NIST SP 800-208 for the LMS and XMSS hash-based
+//! signature schemes.
+//!
+//! The intermediate state of the hashers with a fixed initial hash value can
+//! be exported with `midstate` and resumed with `from_midstate`, as long as
+//! a whole number of blocks has been processed.
+//!
+//! With the `const-fn` feature (requires Rust 1.46) the fixed-output hashers
+//! provide `const_digest`, which computes a digest in a `const` context.
+//!
+//! The compression backend is selected at runtime and can be inspected or
+//! forced with the functions in the [`backend`] module.
 //!
 //! Algorithmically, there are only 2 core algorithms: `Sha256` and `Sha512`.
 //! All other algorithms are just applications of these with different initial
@@ -62,14 +80,18 @@
 #[cfg(feature = "std")]
 extern crate std;
 
+pub mod backend;
 mod consts;
+#[macro_use]
+mod midstate;
 mod sha256;
 mod sha512;
 
 pub use digest::{self, Digest};
+pub use midstate::MidstateError;
 #[cfg(feature = "compress")]
 pub use sha256::compress256;
-pub use sha256::{Sha224, Sha256};
+pub use sha256::{Sha224, Sha256, Sha256_192};
 #[cfg(feature = "compress")]
 pub use sha512::compress512;
-pub use sha512::{Sha384, Sha512, Sha512Trunc224, Sha512Trunc256};
+pub use sha512::{Sha384, Sha512, Sha512Trunc, Sha512Trunc224, Sha512Trunc256, Sha512TruncFixed};
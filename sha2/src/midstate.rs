@@ -0,0 +1,47 @@
+//! Export and import of intermediate hash states.
+use core::fmt;
+
+/// The error type for midstate export and import.
+///
+/// Returned if the number of processed bytes is not a multiple of the block
+/// size or does not fit into the message length counter.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MidstateError;
+
+impl fmt::Display for MidstateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("midstate is not aligned to a block boundary")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MidstateError {}
+
+macro_rules! impl_midstate {
+    ($name:ident, $engine:ident, $word:ty, $len:ty) => {
+        impl $name {
+            /// Export the chaining state and the number of bytes processed
+            /// so far, e.g. to cache the state after a constant prefix.
+            ///
+            /// Returns an error if the input processed so far is not a whole
+            /// number of blocks.
+            pub fn midstate(&self) -> Result<([$word; 8], $len), MidstateError> {
+                self.engine.midstate()
+            }
+
+            /// Create a hasher which resumes from a chaining state exported
+            /// by [`midstate`][Self::midstate] after `bytes_processed` bytes.
+            ///
+            /// Returns an error if `bytes_processed` is not a multiple of the
+            /// block size. Note that [`Reset`] returns the hasher to the
+            /// standard initial hash value, not to the imported state.
+            pub fn from_midstate(
+                state: [$word; 8],
+                bytes_processed: $len,
+            ) -> Result<Self, MidstateError> {
+                let engine = $engine::from_midstate(state, bytes_processed)?;
+                Ok($name { engine })
+            }
+        }
+    };
+}
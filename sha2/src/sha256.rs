@@ -1,8 +1,10 @@
 //! SHA-256
+use crate::backend::{Backend, Override, UnsupportedBackend};
 use crate::consts::{H224, H256, STATE_LEN};
+use crate::midstate::MidstateError;
 use block_buffer::BlockBuffer;
 use core::slice::from_ref;
-use digest::consts::{U28, U32, U64};
+use digest::consts::{U24, U28, U32, U64};
 use digest::generic_array::GenericArray;
 use digest::{BlockInput, FixedOutputDirty, Reset, Update};
 
@@ -45,6 +47,24 @@ impl Engine256 {
         self.buffer.reset();
         self.state = *h;
     }
+
+    fn midstate(&self) -> Result<([u32; STATE_LEN], u64), MidstateError> {
+        if self.buffer.position() != 0 {
+            return Err(MidstateError);
+        }
+        Ok((self.state, self.len >> 3))
+    }
+
+    fn from_midstate(state: [u32; STATE_LEN], bytes: u64) -> Result<Engine256, MidstateError> {
+        if bytes & 0x3F != 0 || bytes > !0u64 >> 3 {
+            return Err(MidstateError);
+        }
+        Ok(Engine256 {
+            len: bytes << 3,
+            buffer: Default::default(),
+            state,
+        })
+    }
 }
 
 /// The SHA-256 hash algorithm with the SHA-256 initial hash value.
@@ -132,26 +152,188 @@ impl Reset for Sha224 {
     }
 }
 
+/// The SHA-256 hash algorithm with the SHA-256 initial hash value. The result
+/// is truncated to 192 bits.
+///
+/// This is SHA-256/192 as specified in NIST SP 800-208, used by the
+/// `LMS_SHA256_M24_*` and `LMOTS_SHA256_N24_*` parameter sets of LMS and the
+/// `SHA2_192` parameter sets of XMSS.
+#[derive(Clone)]
+pub struct Sha256_192 {
+    engine: Engine256,
+}
+
+impl Default for Sha256_192 {
+    fn default() -> Self {
+        Sha256_192 {
+            engine: Engine256::new(&H256),
+        }
+    }
+}
+
+impl BlockInput for Sha256_192 {
+    type BlockSize = BlockSize;
+}
+
+impl Update for Sha256_192 {
+    fn update(&mut self, input: impl AsRef<[u8]>) {
+        self.engine.update(input.as_ref());
+    }
+}
+
+impl FixedOutputDirty for Sha256_192 {
+    type OutputSize = U24;
+
+    fn finalize_into_dirty(&mut self, out: &mut digest::Output<Self>) {
+        self.engine.finish();
+        let s = &self.engine.state[..6];
+        for (chunk, v) in out.chunks_exact_mut(4).zip(s.iter()) {
+            chunk.copy_from_slice(&v.to_be_bytes());
+        }
+    }
+}
+
+impl Reset for Sha256_192 {
+    fn reset(&mut self) {
+        self.engine.reset(&H256);
+    }
+}
+
+impl Sha256 {
+    /// Compute SHA-256 digests of multiple independent messages and write
+    /// them into `outputs`.
+    ///
+    /// On x86 CPUs the messages are hashed in parallel, one message per
+    /// vector lane: 8 lanes with AVX2 and 4 lanes with SSE2. Results are
+    /// identical to hashing each message with [`Sha256::digest`][digest::Digest::digest].
+    ///
+    /// # Panics
+    ///
+    /// If `inputs` and `outputs` have different lengths.
+    pub fn digest_many_into(inputs: &[&[u8]], outputs: &mut [digest::Output<Self>]) {
+        multi::hash_many(inputs, outputs);
+    }
+
+    /// Compute SHA-256 digests of multiple independent messages, see
+    /// [`digest_many_into`][Self::digest_many_into].
+    #[cfg(feature = "std")]
+    pub fn digest_many(inputs: &[&[u8]]) -> std::vec::Vec<digest::Output<Self>> {
+        let mut res = std::vec![Default::default(); inputs.len()];
+        multi::hash_many(inputs, &mut res);
+        res
+    }
+
+    /// Alias of [`digest_many_into`][Self::digest_many_into].
+    pub fn hash_many(inputs: &[&[u8]], outputs: &mut [digest::Output<Self>]) {
+        multi::hash_many(inputs, outputs);
+    }
+
+    /// Compute SHA-256 of the 64-byte concatenation of `left` and `right`,
+    /// e.g. to hash two child nodes of a Merkle tree.
+    ///
+    /// The result is identical to hashing the concatenation with
+    /// [`Sha256::digest`][digest::Digest::digest], but the final padding
+    /// block is processed with a precomputed message schedule.
+    pub fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        pair::hash_pair(left, right)
+    }
+
+    /// Compute [`hash_pair`][Self::hash_pair] for each pair in `pairs` and
+    /// write the results into `outputs`.
+    ///
+    /// # Panics
+    ///
+    /// If `pairs` and `outputs` have different lengths.
+    pub fn hash_pairs(pairs: &[[[u8; 32]; 2]], outputs: &mut [[u8; 32]]) {
+        pair::hash_pairs(pairs, outputs);
+    }
+}
+
+#[cfg(feature = "const-fn")]
+impl Sha224 {
+    /// Compute the SHA-224 digest of `data` in a `const` context.
+    pub const fn const_digest(data: &[u8]) -> [u8; 28] {
+        const_fn::sha224(data)
+    }
+}
+
+#[cfg(feature = "const-fn")]
+impl Sha256_192 {
+    /// Compute the SHA-256/192 digest of `data` in a `const` context.
+    pub const fn const_digest(data: &[u8]) -> [u8; 24] {
+        const_fn::sha256_192(data)
+    }
+}
+
+#[cfg(feature = "const-fn")]
+impl Sha256 {
+    /// Compute the SHA-256 digest of `data` in a `const` context.
+    ///
+    /// This is much slower than [`Sha256::digest`][digest::Digest::digest]
+    /// and is intended for computing constants at compile time:
+    ///
+    /// ```
+    /// use hex_literal::hex;
+    /// use sha2::Sha256;
+    ///
+    /// const HASH: [u8; 32] = Sha256::const_digest(b"hello world");
+    ///
+    /// assert_eq!(HASH, hex!("
+    ///     b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9
+    /// "));
+    /// ```
+    pub const fn const_digest(data: &[u8]) -> [u8; 32] {
+        const_fn::sha256(data)
+    }
+}
+
+impl_midstate!(Sha224, Engine256, u32, u64);
+impl_midstate!(Sha256, Engine256, u32, u64);
+impl_midstate!(Sha256_192, Engine256, u32, u64);
+
 opaque_debug::implement!(Sha224);
 opaque_debug::implement!(Sha256);
+opaque_debug::implement!(Sha256_192);
 
 digest::impl_write!(Sha224);
 digest::impl_write!(Sha256);
+digest::impl_write!(Sha256_192);
+
+// the `const-fn` feature requires Rust 1.46
+#[cfg(feature = "const-fn")]
+#[clippy::msrv = "1.46"]
+mod const_fn;
+mod multi;
+mod pair;
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "force-soft")] {
         mod soft;
         use soft::compress;
+        use crate::backend::shared::soft_only::{detect, is_supported};
     } else if #[cfg(all(feature = "asm", target_arch = "aarch64", target_os = "linux"))] {
         mod soft;
         mod aarch64;
-        use aarch64::compress;
+        use aarch64::{compress, detect, is_supported};
     } else if #[cfg(all(feature = "compress", any(target_arch = "x86", target_arch = "x86_64")))] {
         mod soft;
         mod x86;
-        use x86::compress;
+        use x86::{compress, detect, is_supported};
     } else if #[cfg(all(feature = "asm", any(target_arch = "x86", target_arch = "x86_64")))] {
+        mod soft;
+
+        fn detect() -> Backend {
+            Backend::Asm
+        }
+
+        fn is_supported(backend: Backend) -> bool {
+            backend == Backend::Asm || backend == Backend::Soft
+        }
+
         fn compress(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+            if backend() == Backend::Soft {
+                return soft::compress(state, blocks);
+            }
             for block in blocks {
                 sha2_asm::compress256(state, block);
             }
@@ -159,10 +341,28 @@ cfg_if::cfg_if! {
     } else if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
         mod soft;
         mod x86;
-        use x86::compress;
+        use x86::{compress, detect, is_supported};
     } else {
         mod soft;
         use soft::compress;
+        use crate::backend::shared::soft_only::{detect, is_supported};
+    }
+}
+
+static BACKEND: Override = Override::new();
+
+/// Returns the forced backend or the fastest one supported by the CPU.
+pub(crate) fn backend() -> Backend {
+    BACKEND.get().unwrap_or_else(detect)
+}
+
+pub(crate) fn force_backend(backend: Option<Backend>) -> Result<(), UnsupportedBackend> {
+    match backend {
+        Some(b) if !is_supported(b) => Err(UnsupportedBackend),
+        _ => {
+            BACKEND.set(backend);
+            Ok(())
+        }
     }
 }
 
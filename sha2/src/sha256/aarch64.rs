@@ -1,3 +1,4 @@
+use crate::backend::Backend;
 use libc::{getauxval, AT_HWCAP, HWCAP_SHA2};
 
 #[inline(always)]
@@ -6,10 +7,26 @@ pub fn sha2_supported() -> bool {
     (hwcaps & HWCAP_SHA2) != 0
 }
 
-pub fn compress(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+pub fn detect() -> Backend {
     // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
     // after stabilization
     if sha2_supported() {
+        Backend::Aarch64
+    } else {
+        Backend::Soft
+    }
+}
+
+pub fn is_supported(backend: Backend) -> bool {
+    match backend {
+        Backend::Soft => true,
+        Backend::Aarch64 => sha2_supported(),
+        _ => false,
+    }
+}
+
+pub fn compress(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    if super::backend() == Backend::Aarch64 {
         for block in blocks {
             sha2_asm::compress256(state, block);
         }
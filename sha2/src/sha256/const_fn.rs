@@ -0,0 +1,117 @@
+//! SHA-256 implementation which can be evaluated at compile time.
+#![allow(clippy::many_single_char_names)]
+use crate::consts::{H224, H256, K32, STATE_LEN};
+
+/// Returns the byte at `pos` of the padded message, where `total` is the
+/// length of the padded message in bytes.
+const fn padded_byte(data: &[u8], pos: usize, total: usize) -> u8 {
+    let len = data.len();
+    if pos < len {
+        data[pos]
+    } else if pos == len {
+        0x80
+    } else if pos >= total - 8 {
+        let bits = (len as u64) << 3;
+        (bits >> (8 * (total - 1 - pos))) as u8
+    } else {
+        0
+    }
+}
+
+const fn compress(
+    state: [u32; STATE_LEN],
+    data: &[u8],
+    block: usize,
+    total: usize,
+) -> [u32; STATE_LEN] {
+    let mut w = [0u32; 64];
+    let mut t = 0;
+    while t < 16 {
+        let p = 64 * block + 4 * t;
+        w[t] = u32::from_be_bytes([
+            padded_byte(data, p, total),
+            padded_byte(data, p + 1, total),
+            padded_byte(data, p + 2, total),
+            padded_byte(data, p + 3, total),
+        ]);
+        t += 1;
+    }
+    while t < 64 {
+        let x = w[t - 15];
+        let s0 = x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3);
+        let x = w[t - 2];
+        let s1 = x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10);
+        w[t] = w[t - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[t - 7])
+            .wrapping_add(s1);
+        t += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    let mut t = 0;
+    while t < 64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K32[t])
+            .wrapping_add(w[t]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+        t += 1;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+        state[5].wrapping_add(f),
+        state[6].wrapping_add(g),
+        state[7].wrapping_add(h),
+    ]
+}
+
+/// Hashes `data` starting from the initial hash value `h`.
+const fn digest(h: [u32; STATE_LEN], data: &[u8]) -> [u32; STATE_LEN] {
+    let blocks = (data.len() + 9 + 63) / 64;
+    let mut state = h;
+    let mut i = 0;
+    while i < blocks {
+        state = compress(state, data, i, 64 * blocks);
+        i += 1;
+    }
+    state
+}
+
+macro_rules! impl_digest {
+    ($name:ident, $h:ident, $len:expr) => {
+        pub(super) const fn $name(data: &[u8]) -> [u8; $len] {
+            let state = digest($h, data);
+            let mut out = [0u8; $len];
+            let mut i = 0;
+            while i < $len {
+                out[i] = (state[i / 4] >> (24 - 8 * (i % 4))) as u8;
+                i += 1;
+            }
+            out
+        }
+    };
+}
+
+impl_digest!(sha224, H224, 28);
+impl_digest!(sha256, H256, 32);
+impl_digest!(sha256_192, H256, 24);
@@ -0,0 +1,126 @@
+//! Multi-buffer SHA-256: independent messages are hashed in parallel
+//! by interleaving one message per SIMD lane.
+use crate::consts::{H256, STATE_LEN};
+
+#[cfg(all(
+    not(feature = "force-soft"),
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+mod x86;
+
+/// Maximum number of lanes supported by any backend.
+const MAX_LANES: usize = 8;
+
+/// Compression function which processes one block for each lane.
+type CompressLanes = fn(&mut [[u32; STATE_LEN]; MAX_LANES], &[[u8; 64]; MAX_LANES]);
+
+/// Compute SHA-256 digests of `inputs` and write them into `outputs`.
+pub(super) fn hash_many<O: AsMut<[u8]>>(inputs: &[&[u8]], outputs: &mut [O]) {
+    assert_eq!(
+        inputs.len(),
+        outputs.len(),
+        "number of outputs must be equal to number of inputs"
+    );
+
+    #[cfg(all(
+        not(feature = "force-soft"),
+        any(target_arch = "x86", target_arch = "x86_64")
+    ))]
+    {
+        // a backend forced at runtime is used for each message in turn
+        if super::BACKEND.get().is_none() {
+            if let Some((lanes, compress)) = x86::detect() {
+                hash_lanes(lanes, compress, inputs, outputs);
+                return;
+            }
+        }
+    }
+
+    hash_lanes(1, compress_soft, inputs, outputs);
+}
+
+fn compress_soft(state: &mut [[u32; STATE_LEN]; MAX_LANES], blocks: &[[u8; 64]; MAX_LANES]) {
+    super::compress(&mut state[0], core::slice::from_ref(&blocks[0]));
+}
+
+/// Hash `inputs` with the given `compress` function using `lanes` lanes.
+///
+/// Each lane processes one message at a time. As soon as a lane finishes its
+/// message, it picks up the next one, so messages of unequal length keep all
+/// lanes busy.
+fn hash_lanes<O: AsMut<[u8]>>(
+    lanes: usize,
+    compress: CompressLanes,
+    inputs: &[&[u8]],
+    outputs: &mut [O],
+) {
+    debug_assert!(lanes <= MAX_LANES);
+    let mut state = [H256; MAX_LANES];
+    let mut blocks = [[0u8; 64]; MAX_LANES];
+    // index of the message, index of the next block and number of blocks
+    let mut jobs: [Option<(usize, usize, usize)>; MAX_LANES] = [None; MAX_LANES];
+    let mut next = 0;
+
+    loop {
+        let mut active = false;
+        for lane in 0..lanes {
+            if jobs[lane].is_none() && next < inputs.len() {
+                jobs[lane] = Some((next, 0, num_blocks(inputs[next].len())));
+                state[lane] = H256;
+                next += 1;
+            }
+            if let Some((idx, i, n)) = jobs[lane] {
+                read_block(inputs[idx], i, n, &mut blocks[lane]);
+                active = true;
+            }
+        }
+        if !active {
+            break;
+        }
+
+        compress(&mut state, &blocks);
+
+        for lane in 0..lanes {
+            if let Some((idx, i, n)) = jobs[lane] {
+                if i + 1 == n {
+                    write_output(&state[lane], outputs[idx].as_mut());
+                    jobs[lane] = None;
+                } else {
+                    jobs[lane] = Some((idx, i + 1, n));
+                }
+            }
+        }
+    }
+}
+
+/// Number of blocks in the padded message of length `len`.
+fn num_blocks(len: usize) -> usize {
+    (len + 9 + 63) / 64
+}
+
+/// Copy the `i`-th block of the padded message into `block`, where `n` is
+/// the total number of blocks.
+fn read_block(msg: &[u8], i: usize, n: usize, block: &mut [u8; 64]) {
+    let start = 64 * i;
+    if start + 64 <= msg.len() {
+        block.copy_from_slice(&msg[start..start + 64]);
+        return;
+    }
+
+    *block = [0u8; 64];
+    if start <= msg.len() {
+        let rem = &msg[start..];
+        block[..rem.len()].copy_from_slice(rem);
+        block[rem.len()] = 0x80;
+    }
+    if i + 1 == n {
+        let len = (msg.len() as u64) << 3;
+        block[56..].copy_from_slice(&len.to_be_bytes());
+    }
+}
+
+fn write_output(state: &[u32; STATE_LEN], out: &mut [u8]) {
+    for (chunk, v) in out.chunks_exact_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&v.to_be_bytes());
+    }
+}
@@ -0,0 +1,205 @@
+#![allow(clippy::many_single_char_names)]
+
+use super::{CompressLanes, MAX_LANES};
+use crate::consts::{K32, STATE_LEN};
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Select the widest available backend, returning the number of lanes and
+/// the compression function.
+pub(super) fn detect() -> Option<(usize, CompressLanes)> {
+    // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
+    // after stabilization
+    if cpuid_bool::cpuid_bool!("avx2") {
+        Some((8, compress_avx2))
+    } else if cpuid_bool::cpuid_bool!("sse2") {
+        Some((4, compress_sse2))
+    } else {
+        None
+    }
+}
+
+fn compress_avx2(state: &mut [[u32; STATE_LEN]; MAX_LANES], blocks: &[[u8; 64]; MAX_LANES]) {
+    unsafe { avx2::compress(state, blocks) }
+}
+
+fn compress_sse2(state: &mut [[u32; STATE_LEN]; MAX_LANES], blocks: &[[u8; 64]; MAX_LANES]) {
+    unsafe { sse2::compress(state, blocks) }
+}
+
+macro_rules! impl_compress {
+    (
+        $feature:expr, $lanes:expr, $vec:ty,
+        $load:ident, $store:ident, $set1:ident, $add:ident,
+        $xor:ident, $and:ident, $andnot:ident, $or:ident,
+        $srli:ident, $slli:ident
+    ) => {
+        use super::*;
+
+        /// Compress one block for each of the first lanes of `state`,
+        /// keeping one lane per 32-bit vector element.
+        #[target_feature(enable = $feature)]
+        pub(super) unsafe fn compress(
+            state: &mut [[u32; STATE_LEN]; MAX_LANES],
+            blocks: &[[u8; 64]; MAX_LANES],
+        ) {
+            let mut tmp = [0u32; $lanes];
+
+            let mut w = [$set1(0); 64];
+            for (t, w) in w[..16].iter_mut().enumerate() {
+                for (v, block) in tmp.iter_mut().zip(blocks.iter()) {
+                    let mut word = [0u8; 4];
+                    word.copy_from_slice(&block[4 * t..4 * t + 4]);
+                    *v = u32::from_be_bytes(word);
+                }
+                *w = $load(tmp.as_ptr() as *const $vec);
+            }
+            for t in 16..64 {
+                let x = w[t - 15];
+                let s0 = $xor(
+                    $xor(
+                        $or($srli(x, 7), $slli(x, 25)),
+                        $or($srli(x, 18), $slli(x, 14)),
+                    ),
+                    $srli(x, 3),
+                );
+                let x = w[t - 2];
+                let s1 = $xor(
+                    $xor(
+                        $or($srli(x, 17), $slli(x, 15)),
+                        $or($srli(x, 19), $slli(x, 13)),
+                    ),
+                    $srli(x, 10),
+                );
+                w[t] = $add($add(w[t - 16], s0), $add(w[t - 7], s1));
+            }
+
+            let mut h = [$set1(0); STATE_LEN];
+            for (i, h) in h.iter_mut().enumerate() {
+                for (v, s) in tmp.iter_mut().zip(state.iter()) {
+                    *v = s[i];
+                }
+                *h = $load(tmp.as_ptr() as *const $vec);
+            }
+
+            let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+            for (w, &k) in w.iter().zip(K32.iter()) {
+                let s1 = $xor(
+                    $xor(
+                        $or($srli(e, 6), $slli(e, 26)),
+                        $or($srli(e, 11), $slli(e, 21)),
+                    ),
+                    $or($srli(e, 25), $slli(e, 7)),
+                );
+                let ch = $xor($and(e, f), $andnot(e, g));
+                let t1 = $add($add($add(hh, s1), $add(ch, $set1(k as i32))), *w);
+                let s0 = $xor(
+                    $xor(
+                        $or($srli(a, 2), $slli(a, 30)),
+                        $or($srli(a, 13), $slli(a, 19)),
+                    ),
+                    $or($srli(a, 22), $slli(a, 10)),
+                );
+                let maj = $xor($xor($and(a, b), $and(a, c)), $and(b, c));
+                let t2 = $add(s0, maj);
+
+                hh = g;
+                g = f;
+                f = e;
+                e = $add(d, t1);
+                d = c;
+                c = b;
+                b = a;
+                a = $add(t1, t2);
+            }
+
+            let res = [a, b, c, d, e, f, g, hh];
+            for (i, (h, r)) in h.iter().zip(res.iter()).enumerate() {
+                $store(tmp.as_mut_ptr() as *mut $vec, $add(*h, *r));
+                for (v, s) in tmp.iter().zip(state.iter_mut()) {
+                    s[i] = *v;
+                }
+            }
+        }
+    };
+}
+
+mod avx2 {
+    impl_compress!(
+        "avx2",
+        8,
+        __m256i,
+        _mm256_loadu_si256,
+        _mm256_storeu_si256,
+        _mm256_set1_epi32,
+        _mm256_add_epi32,
+        _mm256_xor_si256,
+        _mm256_and_si256,
+        _mm256_andnot_si256,
+        _mm256_or_si256,
+        _mm256_srli_epi32,
+        _mm256_slli_epi32
+    );
+}
+
+mod sse2 {
+    impl_compress!(
+        "sse2",
+        4,
+        __m128i,
+        _mm_loadu_si128,
+        _mm_storeu_si128,
+        _mm_set1_epi32,
+        _mm_add_epi32,
+        _mm_xor_si128,
+        _mm_and_si128,
+        _mm_andnot_si128,
+        _mm_or_si128,
+        _mm_srli_epi32,
+        _mm_slli_epi32
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::hash_lanes;
+    use super::*;
+    use crate::{Digest, Sha256};
+
+    // lengths around the padding boundaries, so that lanes finish at
+    // different times
+    const LENGTHS: [usize; 14] = [0, 1, 55, 56, 63, 64, 65, 119, 120, 128, 1000, 3, 4096, 777];
+
+    fn check(lanes: usize, compress: CompressLanes) {
+        let mut data = [0u8; 4096 + 16];
+        for (i, v) in data.iter_mut().enumerate() {
+            *v = (i * 31) as u8;
+        }
+        let mut inputs = [&data[..0]; 14];
+        for (i, (input, &n)) in inputs.iter_mut().zip(LENGTHS.iter()).enumerate() {
+            *input = &data[i..i + n];
+        }
+        let mut outputs = [[0u8; 32]; 14];
+        hash_lanes(lanes, compress, &inputs, &mut outputs);
+        for (out, msg) in outputs.iter().zip(inputs.iter()) {
+            assert_eq!(out[..], Sha256::digest(msg)[..]);
+        }
+    }
+
+    #[test]
+    fn compress_sse2_matches_digest() {
+        if cpuid_bool::cpuid_bool!("sse2") {
+            check(4, compress_sse2);
+        }
+    }
+
+    #[test]
+    fn compress_avx2_matches_digest() {
+        if cpuid_bool::cpuid_bool!("avx2") {
+            check(8, compress_avx2);
+        }
+    }
+}
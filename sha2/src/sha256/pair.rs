@@ -0,0 +1,88 @@
+//! SHA-256 of 64-byte messages, e.g. two 32-byte child nodes of a Merkle
+//! tree. The message fills exactly one block, so the second block consists
+//! only of padding and its message schedule is precomputed.
+#![allow(clippy::many_single_char_names)]
+use crate::consts::{H256, PAD64_WK32, STATE_LEN};
+use core::slice::{from_mut, from_ref};
+
+#[cfg(all(
+    not(feature = "force-soft"),
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+mod x86;
+
+/// Compute SHA-256 of the concatenation of `left` and `right`.
+pub(super) fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    hash_pairs(from_ref(&[*left, *right]), from_mut(&mut out));
+    out
+}
+
+/// Compute SHA-256 of the concatenation of each pair in `pairs` and write
+/// the results into `outputs`.
+pub(super) fn hash_pairs(pairs: &[[[u8; 32]; 2]], outputs: &mut [[u8; 32]]) {
+    assert_eq!(
+        pairs.len(),
+        outputs.len(),
+        "number of outputs must be equal to number of pairs"
+    );
+
+    #[cfg(all(
+        not(feature = "force-soft"),
+        any(target_arch = "x86", target_arch = "x86_64")
+    ))]
+    {
+        if let Some(compress_padding) = x86::detect() {
+            hash_pairs_with(compress_padding, pairs, outputs);
+            return;
+        }
+    }
+
+    hash_pairs_with(compress_padding_soft, pairs, outputs);
+}
+
+fn hash_pairs_with(
+    compress_padding: fn(&mut [u32; STATE_LEN]),
+    pairs: &[[[u8; 32]; 2]],
+    outputs: &mut [[u8; 32]],
+) {
+    let mut block = [0u8; 64];
+    for (pair, out) in pairs.iter().zip(outputs.iter_mut()) {
+        block[..32].copy_from_slice(&pair[0]);
+        block[32..].copy_from_slice(&pair[1]);
+
+        let mut state = H256;
+        super::compress(&mut state, from_ref(&block));
+        compress_padding(&mut state);
+
+        for (chunk, v) in out.chunks_exact_mut(4).zip(state.iter()) {
+            chunk.copy_from_slice(&v.to_be_bytes());
+        }
+    }
+}
+
+/// Process the padding block of a 64-byte message.
+fn compress_padding_soft(state: &mut [u32; STATE_LEN]) {
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for wk in PAD64_WK32.iter() {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(*wk);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+        *s = s.wrapping_add(*v);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::backend::Backend;
+use crate::consts::{PAD64_WK32, STATE_LEN};
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Returns the SHA-NI padding block function if SHA-NI is the active
+/// backend.
+pub(super) fn detect() -> Option<fn(&mut [u32; STATE_LEN])> {
+    if super::super::backend() == Backend::ShaNi {
+        Some(compress_padding_shani)
+    } else {
+        None
+    }
+}
+
+fn compress_padding_shani(state: &mut [u32; STATE_LEN]) {
+    unsafe { compress_padding(state) }
+}
+
+// we use unaligned loads with `__m128i` pointers
+#[allow(clippy::cast_ptr_alignment)]
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn compress_padding(state: &mut [u32; STATE_LEN]) {
+    let state_ptr = state.as_ptr() as *const __m128i;
+    let dcba = _mm_loadu_si128(state_ptr.add(0));
+    let efgh = _mm_loadu_si128(state_ptr.add(1));
+
+    let cdab = _mm_shuffle_epi32(dcba, 0xB1);
+    let efgh = _mm_shuffle_epi32(efgh, 0x1B);
+    let mut abef = _mm_alignr_epi8(cdab, efgh, 8);
+    let mut cdgh = _mm_blend_epi16(efgh, cdab, 0xF0);
+    let abef_save = abef;
+    let cdgh_save = cdgh;
+
+    // the message schedule is constant, so only the rounds are computed
+    for wk in PAD64_WK32.chunks_exact(4) {
+        let t1 = _mm_loadu_si128(wk.as_ptr() as *const __m128i);
+        cdgh = _mm_sha256rnds2_epu32(cdgh, abef, t1);
+        let t2 = _mm_shuffle_epi32(t1, 0x0E);
+        abef = _mm_sha256rnds2_epu32(abef, cdgh, t2);
+    }
+
+    abef = _mm_add_epi32(abef, abef_save);
+    cdgh = _mm_add_epi32(cdgh, cdgh_save);
+
+    let feba = _mm_shuffle_epi32(abef, 0x1B);
+    let dchg = _mm_shuffle_epi32(cdgh, 0xB1);
+    let dcba = _mm_blend_epi16(feba, dchg, 0xF0);
+    let hgef = _mm_alignr_epi8(dchg, feba, 8);
+
+    let state_ptr_mut = state.as_mut_ptr() as *mut __m128i;
+    _mm_storeu_si128(state_ptr_mut.add(0), dcba);
+    _mm_storeu_si128(state_ptr_mut.add(1), hgef);
+}
@@ -1,5 +1,9 @@
 #![allow(clippy::many_single_char_names)]
 
+mod vector;
+
+use crate::backend::Backend;
+
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::*;
 #[cfg(target_arch = "x86")]
@@ -95,14 +99,41 @@ unsafe fn digest_blocks(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
     _mm_storeu_si128(state_ptr_mut.add(1), hgef);
 }
 
-pub fn compress(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+pub fn detect() -> Backend {
     // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
     // after stabilization
     if cpuid_bool::cpuid_bool!("sha", "sse2", "ssse3", "sse4.1") {
-        unsafe {
-            digest_blocks(state, blocks);
-        }
+        Backend::ShaNi
+    } else if cpuid_bool::cpuid_bool!("avx2") {
+        Backend::Avx2
+    } else if cpuid_bool::cpuid_bool!("ssse3") {
+        Backend::Ssse3
     } else {
-        super::soft::compress(state, blocks);
+        Backend::Soft
+    }
+}
+
+pub fn is_supported(backend: Backend) -> bool {
+    match backend {
+        Backend::Soft => true,
+        Backend::ShaNi => cpuid_bool::cpuid_bool!("sha", "sse2", "ssse3", "sse4.1"),
+        Backend::Avx2 => cpuid_bool::cpuid_bool!("avx2"),
+        Backend::Ssse3 => cpuid_bool::cpuid_bool!("ssse3"),
+        _ => false,
+    }
+}
+
+pub fn compress(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    match super::backend() {
+        Backend::ShaNi => unsafe {
+            digest_blocks(state, blocks);
+        },
+        Backend::Avx2 => unsafe {
+            vector::digest_blocks_avx2(state, blocks);
+        },
+        Backend::Ssse3 => unsafe {
+            vector::digest_blocks_ssse3(state, blocks);
+        },
+        _ => super::soft::compress(state, blocks),
     }
 }
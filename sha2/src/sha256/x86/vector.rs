@@ -0,0 +1,240 @@
+//! SHA-256 compression for CPUs without the SHA extensions. The message
+//! schedule is computed with SSSE3 or AVX2 vectors, while the rounds are
+//! computed with scalar code. The AVX2 backend schedules two blocks at once,
+//! one in each 128-bit lane. AVX without AVX2 has no wider integer vectors
+//! than SSSE3, so such CPUs use the SSSE3 backend.
+#![allow(clippy::many_single_char_names)]
+use crate::consts::K32;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+macro_rules! impl_schedule {
+    (
+        $name:ident, $feature:expr, $vec:ty,
+        $add:ident, $xor:ident, $or:ident, $srli:ident, $slli:ident,
+        $alignr:ident, $bsrli:ident, $bslli:ident
+    ) => {
+        /// Computes the next 4 words of the message schedule from the
+        /// previous 16 words stored in `x0..x3` (in each 128-bit lane).
+        #[inline]
+        #[target_feature(enable = $feature)]
+        unsafe fn $name(x0: $vec, x1: $vec, x2: $vec, x3: $vec) -> $vec {
+            // W[t - 15] and W[t - 7]
+            let w15 = $alignr(x1, x0, 4);
+            let w7 = $alignr(x3, x2, 4);
+
+            let s0 = $xor(
+                $xor(
+                    $or($srli(w15, 7), $slli(w15, 25)),
+                    $or($srli(w15, 18), $slli(w15, 14)),
+                ),
+                $srli(w15, 3),
+            );
+            let t = $add($add(x0, s0), w7);
+
+            // sigma1(W[t - 2]) is available only for the lower two words,
+            // the upper two depend on the words computed in the first step
+            let w2 = $bsrli(x3, 8);
+            let s1 = $xor(
+                $xor(
+                    $or($srli(w2, 17), $slli(w2, 15)),
+                    $or($srli(w2, 19), $slli(w2, 13)),
+                ),
+                $srli(w2, 10),
+            );
+            let lo = $add(t, s1);
+
+            let w2 = $bslli(lo, 8);
+            let s1 = $xor(
+                $xor(
+                    $or($srli(w2, 17), $slli(w2, 15)),
+                    $or($srli(w2, 19), $slli(w2, 13)),
+                ),
+                $srli(w2, 10),
+            );
+            $add(lo, s1)
+        }
+    };
+}
+
+impl_schedule!(
+    schedule_ssse3,
+    "ssse3",
+    __m128i,
+    _mm_add_epi32,
+    _mm_xor_si128,
+    _mm_or_si128,
+    _mm_srli_epi32,
+    _mm_slli_epi32,
+    _mm_alignr_epi8,
+    _mm_srli_si128,
+    _mm_slli_si128
+);
+
+impl_schedule!(
+    schedule_avx2,
+    "avx2",
+    __m256i,
+    _mm256_add_epi32,
+    _mm256_xor_si256,
+    _mm256_or_si256,
+    _mm256_srli_epi32,
+    _mm256_slli_epi32,
+    _mm256_alignr_epi8,
+    _mm256_srli_si256,
+    _mm256_slli_si256
+);
+
+/// Performs 64 rounds using the precomputed sum of message words and round
+/// constants.
+#[inline(always)]
+fn rounds(state: &mut [u32; 8], wk: &[u32; 64]) {
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for wk in wk.iter() {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(*wk);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+        *s = s.wrapping_add(*v);
+    }
+}
+
+// we use unaligned loads with `__m128i` and `__m256i` pointers
+#[allow(clippy::cast_ptr_alignment)]
+#[target_feature(enable = "ssse3")]
+pub unsafe fn digest_blocks_ssse3(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    let mask = _mm_set_epi64x(
+        0x0C0D_0E0F_0809_0A0Bu64 as i64,
+        0x0405_0607_0001_0203u64 as i64,
+    );
+
+    let mut wk = [0u32; 64];
+    for block in blocks {
+        let ptr = block.as_ptr() as *const __m128i;
+        let mut w = [
+            _mm_shuffle_epi8(_mm_loadu_si128(ptr), mask),
+            _mm_shuffle_epi8(_mm_loadu_si128(ptr.add(1)), mask),
+            _mm_shuffle_epi8(_mm_loadu_si128(ptr.add(2)), mask),
+            _mm_shuffle_epi8(_mm_loadu_si128(ptr.add(3)), mask),
+        ];
+
+        for i in 0..16 {
+            if i >= 4 {
+                w[i % 4] = schedule_ssse3(w[i % 4], w[(i + 1) % 4], w[(i + 2) % 4], w[(i + 3) % 4]);
+            }
+            let k = _mm_loadu_si128(K32[4 * i..].as_ptr() as *const __m128i);
+            let dst = wk[4 * i..].as_mut_ptr() as *mut __m128i;
+            _mm_storeu_si128(dst, _mm_add_epi32(w[i % 4], k));
+        }
+
+        rounds(state, &wk);
+    }
+}
+
+/// Loads 4 words from each of two blocks into the lower and upper lane.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn load_pair(p0: *const __m128i, p1: *const __m128i, mask: __m256i) -> __m256i {
+    let lo = _mm256_castsi128_si256(_mm_loadu_si128(p0));
+    _mm256_shuffle_epi8(_mm256_inserti128_si256(lo, _mm_loadu_si128(p1), 1), mask)
+}
+
+#[allow(clippy::cast_ptr_alignment)]
+#[target_feature(enable = "avx2")]
+pub unsafe fn digest_blocks_avx2(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    let mask = _mm256_set_epi64x(
+        0x0C0D_0E0F_0809_0A0Bu64 as i64,
+        0x0405_0607_0001_0203u64 as i64,
+        0x0C0D_0E0F_0809_0A0Bu64 as i64,
+        0x0405_0607_0001_0203u64 as i64,
+    );
+
+    let mut pairs = blocks.chunks_exact(2);
+    let mut wk = [[0u32; 64]; 2];
+    for pair in &mut pairs {
+        let p0 = pair[0].as_ptr() as *const __m128i;
+        let p1 = pair[1].as_ptr() as *const __m128i;
+        let mut w = [
+            load_pair(p0.add(0), p1.add(0), mask),
+            load_pair(p0.add(1), p1.add(1), mask),
+            load_pair(p0.add(2), p1.add(2), mask),
+            load_pair(p0.add(3), p1.add(3), mask),
+        ];
+
+        for i in 0..16 {
+            if i >= 4 {
+                w[i % 4] = schedule_avx2(w[i % 4], w[(i + 1) % 4], w[(i + 2) % 4], w[(i + 3) % 4]);
+            }
+            let k = _mm_loadu_si128(K32[4 * i..].as_ptr() as *const __m128i);
+            let v = _mm256_add_epi32(w[i % 4], _mm256_broadcastsi128_si256(k));
+            let dst0 = wk[0][4 * i..].as_mut_ptr() as *mut __m128i;
+            let dst1 = wk[1][4 * i..].as_mut_ptr() as *mut __m128i;
+            _mm_storeu_si128(dst0, _mm256_castsi256_si128(v));
+            _mm_storeu_si128(dst1, _mm256_extracti128_si256(v, 1));
+        }
+
+        rounds(state, &wk[0]);
+        rounds(state, &wk[1]);
+    }
+
+    digest_blocks_ssse3(state, pairs.remainder());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::soft;
+
+    /// Fills `blocks` with pseudo-random data using xorshift64.
+    fn fill(blocks: &mut [[u8; 64]], seed: u64) {
+        let mut x = seed;
+        for v in blocks.iter_mut().flat_map(|b| b.iter_mut()) {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            *v = x as u8;
+        }
+    }
+
+    fn check(compress: unsafe fn(&mut [u32; 8], &[[u8; 64]])) {
+        let mut blocks = [[0u8; 64]; 9];
+        for n in 0..=blocks.len() {
+            fill(&mut blocks[..n], 0x2545_F491_4F6C_DD1D + n as u64);
+            let mut expected = [n as u32; 8];
+            let mut state = expected;
+            soft::compress(&mut expected, &blocks[..n]);
+            unsafe { compress(&mut state, &blocks[..n]) };
+            assert_eq!(state, expected, "{} blocks", n);
+        }
+    }
+
+    #[test]
+    fn ssse3_matches_soft() {
+        if cpuid_bool::cpuid_bool!("ssse3") {
+            check(super::digest_blocks_ssse3);
+        }
+    }
+
+    #[test]
+    fn avx2_matches_soft() {
+        if cpuid_bool::cpuid_bool!("avx2") {
+            check(super::digest_blocks_avx2);
+        }
+    }
+}
@@ -1,10 +1,13 @@
 //! SHA-512
+use crate::backend::{Backend, Override, UnsupportedBackend};
 use crate::consts::{H384, H512, H512_TRUNC_224, H512_TRUNC_256, STATE_LEN};
+use crate::midstate::MidstateError;
 use block_buffer::BlockBuffer;
+use core::marker::PhantomData;
 use core::slice::from_ref;
 use digest::consts::{U128, U28, U32, U48, U64};
-use digest::generic_array::GenericArray;
-use digest::{BlockInput, FixedOutputDirty, Reset, Update};
+use digest::generic_array::{ArrayLength, GenericArray};
+use digest::{BlockInput, FixedOutputDirty, InvalidOutputSize, Reset, Update, VariableOutputDirty};
 
 type BlockSize = U128;
 
@@ -43,6 +46,72 @@ impl Engine512 {
         self.buffer.reset();
         self.state = *h;
     }
+
+    fn midstate(&self) -> Result<([u64; STATE_LEN], u128), MidstateError> {
+        if self.buffer.position() != 0 {
+            return Err(MidstateError);
+        }
+        Ok((self.state, self.len >> 3))
+    }
+
+    fn from_midstate(state: [u64; STATE_LEN], bytes: u128) -> Result<Engine512, MidstateError> {
+        if bytes & 0x7F != 0 || bytes > !0u128 >> 3 {
+            return Err(MidstateError);
+        }
+        Ok(Engine512 {
+            len: bytes << 3,
+            buffer: Default::default(),
+            state,
+        })
+    }
+
+    /// Write the first `t` bits of the state into `out`, which must be
+    /// `ceil(t / 8)` bytes long. Unused trailing bits are set to zero.
+    fn write_truncated(&self, t: usize, out: &mut [u8]) {
+        let mut buf = [0u8; 64];
+        for (chunk, v) in buf.chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&v.to_be_bytes());
+        }
+        let n = out.len();
+        out.copy_from_slice(&buf[..n]);
+        let rem = t % 8;
+        if rem != 0 {
+            out[n - 1] &= 0xFF << (8 - rem);
+        }
+    }
+}
+
+/// Check that `t` is a valid SHA-512/t output size in bits.
+fn is_valid_trunc(t: usize) -> bool {
+    t > 0 && t < 512 && t != 384
+}
+
+/// Generate the SHA-512/t initial hash value as specified in FIPS 180-4,
+/// section 5.3.6, i.e. by hashing the string "SHA-512/t" with SHA-512
+/// using the modified initial hash value H0''.
+fn trunc_iv(t: usize) -> [u64; STATE_LEN] {
+    debug_assert!(is_valid_trunc(t));
+    let mut h = H512;
+    h.iter_mut().for_each(|v| *v ^= 0xa5a5_a5a5_a5a5_a5a5);
+
+    let digits = [
+        b'0' + (t / 100) as u8,
+        b'0' + (t / 10 % 10) as u8,
+        b'0' + (t % 10) as u8,
+    ];
+    let skip = if t >= 100 {
+        0
+    } else if t >= 10 {
+        1
+    } else {
+        2
+    };
+
+    let mut engine = Engine512::new(&h);
+    engine.update(b"SHA-512/");
+    engine.update(&digits[skip..]);
+    engine.finish();
+    engine.state
 }
 
 /// The SHA-512 hash algorithm with the SHA-512 initial hash value.
@@ -217,29 +286,251 @@ impl Reset for Sha512Trunc224 {
     }
 }
 
+/// The SHA-512/t hash algorithm with an output size chosen at runtime.
+///
+/// The initial hash value is generated on construction, so for `t` equal to
+/// 224 and 256 this type produces the same results as [`Sha512Trunc224`] and
+/// [`Sha512Trunc256`], which use precomputed values instead.
+#[derive(Clone)]
+pub struct Sha512Trunc {
+    engine: Engine512,
+    iv: [u64; STATE_LEN],
+    t: usize,
+}
+
+impl Sha512Trunc {
+    /// Create a new hasher with an output size of `t` bits. If `t` is not
+    /// a multiple of 8, the trailing bits of the last output byte are zero.
+    ///
+    /// Returns an error if `t` is zero, not smaller than 512 or equal to 384.
+    pub fn with_bits(t: usize) -> Result<Self, InvalidOutputSize> {
+        if !is_valid_trunc(t) {
+            return Err(InvalidOutputSize);
+        }
+        let iv = trunc_iv(t);
+        Ok(Sha512Trunc {
+            engine: Engine512::new(&iv),
+            iv,
+            t,
+        })
+    }
+}
+
+impl BlockInput for Sha512Trunc {
+    type BlockSize = BlockSize;
+}
+
+impl Update for Sha512Trunc {
+    fn update(&mut self, input: impl AsRef<[u8]>) {
+        self.engine.update(input.as_ref());
+    }
+}
+
+impl VariableOutputDirty for Sha512Trunc {
+    fn new(output_size: usize) -> Result<Self, InvalidOutputSize> {
+        Self::with_bits(output_size.checked_mul(8).ok_or(InvalidOutputSize)?)
+    }
+
+    fn output_size(&self) -> usize {
+        (self.t + 7) / 8
+    }
+
+    fn finalize_variable_dirty(&mut self, f: impl FnOnce(&[u8])) {
+        self.engine.finish();
+        let mut buf = [0u8; 64];
+        let out = &mut buf[..self.output_size()];
+        self.engine.write_truncated(self.t, out);
+        f(out);
+    }
+}
+
+impl Reset for Sha512Trunc {
+    fn reset(&mut self) {
+        self.engine.reset(&self.iv);
+    }
+}
+
+/// The SHA-512/t hash algorithm with an output size of `N` bytes.
+///
+/// The initial hash value is generated on construction, see [`Sha512Trunc`].
+///
+/// # Panics
+///
+/// Construction panics if `N` is zero, larger than 63 or equal to 48.
+#[derive(Clone)]
+pub struct Sha512TruncFixed<N: ArrayLength<u8>> {
+    engine: Engine512,
+    iv: [u64; STATE_LEN],
+    _output_size: PhantomData<N>,
+}
+
+impl<N: ArrayLength<u8>> Default for Sha512TruncFixed<N> {
+    /// Creates a new hasher for SHA-512/t with `t` equal to `8 * N`.
+    ///
+    /// # Panics
+    ///
+    /// If `N` is zero, larger than 63 or equal to 48.
+    fn default() -> Self {
+        let t = 8 * N::to_usize();
+        assert!(is_valid_trunc(t), "invalid SHA-512/t output size");
+        let iv = trunc_iv(t);
+        Sha512TruncFixed {
+            engine: Engine512::new(&iv),
+            iv,
+            _output_size: PhantomData,
+        }
+    }
+}
+
+impl<N: ArrayLength<u8>> BlockInput for Sha512TruncFixed<N> {
+    type BlockSize = BlockSize;
+}
+
+impl<N: ArrayLength<u8>> Update for Sha512TruncFixed<N> {
+    fn update(&mut self, input: impl AsRef<[u8]>) {
+        self.engine.update(input.as_ref());
+    }
+}
+
+impl<N: ArrayLength<u8>> FixedOutputDirty for Sha512TruncFixed<N> {
+    type OutputSize = N;
+
+    fn finalize_into_dirty(&mut self, out: &mut digest::Output<Self>) {
+        self.engine.finish();
+        self.engine.write_truncated(8 * N::to_usize(), out);
+    }
+}
+
+impl<N: ArrayLength<u8>> Reset for Sha512TruncFixed<N> {
+    fn reset(&mut self) {
+        self.engine.reset(&self.iv);
+    }
+}
+
+impl<N: ArrayLength<u8>> core::fmt::Debug for Sha512TruncFixed<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Sha512TruncFixed { ... }")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<N: ArrayLength<u8>> std::io::Write for Sha512TruncFixed<N> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Update::update(self, buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "const-fn")]
+impl Sha384 {
+    /// Compute the SHA-384 digest of `data` in a `const` context.
+    pub const fn const_digest(data: &[u8]) -> [u8; 48] {
+        const_fn::sha384(data)
+    }
+}
+
+#[cfg(feature = "const-fn")]
+impl Sha512 {
+    /// Compute the SHA-512 digest of `data` in a `const` context.
+    ///
+    /// This is much slower than [`Sha512::digest`][digest::Digest::digest]
+    /// and is intended for computing constants at compile time.
+    pub const fn const_digest(data: &[u8]) -> [u8; 64] {
+        const_fn::sha512(data)
+    }
+}
+
+#[cfg(feature = "const-fn")]
+impl Sha512Trunc224 {
+    /// Compute the SHA-512/224 digest of `data` in a `const` context.
+    pub const fn const_digest(data: &[u8]) -> [u8; 28] {
+        const_fn::sha512_trunc224(data)
+    }
+}
+
+#[cfg(feature = "const-fn")]
+impl Sha512Trunc256 {
+    /// Compute the SHA-512/256 digest of `data` in a `const` context.
+    pub const fn const_digest(data: &[u8]) -> [u8; 32] {
+        const_fn::sha512_trunc256(data)
+    }
+}
+
+impl_midstate!(Sha384, Engine512, u64, u128);
+impl_midstate!(Sha512, Engine512, u64, u128);
+impl_midstate!(Sha512Trunc224, Engine512, u64, u128);
+impl_midstate!(Sha512Trunc256, Engine512, u64, u128);
+
 opaque_debug::implement!(Sha384);
 opaque_debug::implement!(Sha512);
+opaque_debug::implement!(Sha512Trunc);
 opaque_debug::implement!(Sha512Trunc224);
 opaque_debug::implement!(Sha512Trunc256);
 
 digest::impl_write!(Sha384);
 digest::impl_write!(Sha512);
+digest::impl_write!(Sha512Trunc);
 digest::impl_write!(Sha512Trunc224);
 digest::impl_write!(Sha512Trunc256);
 
+// the `const-fn` feature requires Rust 1.46
+#[cfg(feature = "const-fn")]
+#[clippy::msrv = "1.46"]
+mod const_fn;
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "force-soft")] {
         mod soft;
         use soft::compress;
+        use crate::backend::shared::soft_only::{detect, is_supported};
     } else if #[cfg(all(feature = "asm", any(target_arch = "x86", target_arch = "x86_64")))] {
+        mod soft;
+
+        fn detect() -> Backend {
+            Backend::Asm
+        }
+
+        fn is_supported(backend: Backend) -> bool {
+            backend == Backend::Asm || backend == Backend::Soft
+        }
+
         fn compress(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
+            if backend() == Backend::Soft {
+                return soft::compress(state, blocks);
+            }
             for block in blocks {
                 sha2_asm::compress512(state, block);
             }
         }
+    } else if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
+        mod soft;
+        mod x86;
+        use x86::{compress, detect, is_supported};
     } else {
         mod soft;
         use soft::compress;
+        use crate::backend::shared::soft_only::{detect, is_supported};
+    }
+}
+
+static BACKEND: Override = Override::new();
+
+/// Returns the forced backend or the fastest one supported by the CPU.
+pub(crate) fn backend() -> Backend {
+    BACKEND.get().unwrap_or_else(detect)
+}
+
+pub(crate) fn force_backend(backend: Option<Backend>) -> Result<(), UnsupportedBackend> {
+    match backend {
+        Some(b) if !is_supported(b) => Err(UnsupportedBackend),
+        _ => {
+            BACKEND.set(backend);
+            Ok(())
+        }
     }
 }
 
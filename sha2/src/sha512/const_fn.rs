@@ -0,0 +1,122 @@
+//! SHA-512 implementation which can be evaluated at compile time.
+#![allow(clippy::many_single_char_names)]
+use crate::consts::{H384, H512, H512_TRUNC_224, H512_TRUNC_256, K64, STATE_LEN};
+
+/// Returns the byte at `pos` of the padded message, where `total` is the
+/// length of the padded message in bytes.
+const fn padded_byte(data: &[u8], pos: usize, total: usize) -> u8 {
+    let len = data.len();
+    if pos < len {
+        data[pos]
+    } else if pos == len {
+        0x80
+    } else if pos >= total - 16 {
+        let bits = (len as u128) << 3;
+        (bits >> (8 * (total - 1 - pos))) as u8
+    } else {
+        0
+    }
+}
+
+const fn compress(
+    state: [u64; STATE_LEN],
+    data: &[u8],
+    block: usize,
+    total: usize,
+) -> [u64; STATE_LEN] {
+    let mut w = [0u64; 80];
+    let mut t = 0;
+    while t < 16 {
+        let p = 128 * block + 8 * t;
+        w[t] = u64::from_be_bytes([
+            padded_byte(data, p, total),
+            padded_byte(data, p + 1, total),
+            padded_byte(data, p + 2, total),
+            padded_byte(data, p + 3, total),
+            padded_byte(data, p + 4, total),
+            padded_byte(data, p + 5, total),
+            padded_byte(data, p + 6, total),
+            padded_byte(data, p + 7, total),
+        ]);
+        t += 1;
+    }
+    while t < 80 {
+        let x = w[t - 15];
+        let s0 = x.rotate_right(1) ^ x.rotate_right(8) ^ (x >> 7);
+        let x = w[t - 2];
+        let s1 = x.rotate_right(19) ^ x.rotate_right(61) ^ (x >> 6);
+        w[t] = w[t - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[t - 7])
+            .wrapping_add(s1);
+        t += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    let mut t = 0;
+    while t < 80 {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K64[t])
+            .wrapping_add(w[t]);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+        t += 1;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+        state[5].wrapping_add(f),
+        state[6].wrapping_add(g),
+        state[7].wrapping_add(h),
+    ]
+}
+
+/// Hashes `data` starting from the initial hash value `h`.
+const fn digest(h: [u64; STATE_LEN], data: &[u8]) -> [u64; STATE_LEN] {
+    let blocks = (data.len() + 17 + 127) / 128;
+    let mut state = h;
+    let mut i = 0;
+    while i < blocks {
+        state = compress(state, data, i, 128 * blocks);
+        i += 1;
+    }
+    state
+}
+
+macro_rules! impl_digest {
+    ($name:ident, $h:ident, $len:expr) => {
+        pub(super) const fn $name(data: &[u8]) -> [u8; $len] {
+            let state = digest($h, data);
+            let mut out = [0u8; $len];
+            let mut i = 0;
+            while i < $len {
+                out[i] = (state[i / 8] >> (56 - 8 * (i % 8))) as u8;
+                i += 1;
+            }
+            out
+        }
+    };
+}
+
+impl_digest!(sha384, H384, 48);
+impl_digest!(sha512, H512, 64);
+impl_digest!(sha512_trunc224, H512_TRUNC_224, 28);
+impl_digest!(sha512_trunc256, H512_TRUNC_256, 32);
@@ -0,0 +1,134 @@
+//! SHA-512 compression with the message schedule vectorized using AVX2.
+#![allow(clippy::many_single_char_names)]
+use crate::backend::Backend;
+use crate::consts::K64;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+macro_rules! rotr64 {
+    ($x:expr, $n:expr) => {
+        _mm256_or_si256(_mm256_srli_epi64($x, $n), _mm256_slli_epi64($x, 64 - $n))
+    };
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn sigma0(x: __m256i) -> __m256i {
+    _mm256_xor_si256(
+        _mm256_xor_si256(rotr64!(x, 1), rotr64!(x, 8)),
+        _mm256_srli_epi64(x, 7),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn sigma1(x: __m256i) -> __m256i {
+    _mm256_xor_si256(
+        _mm256_xor_si256(rotr64!(x, 19), rotr64!(x, 61)),
+        _mm256_srli_epi64(x, 6),
+    )
+}
+
+/// Returns `[a[1], a[2], a[3], b[0]]`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn shift1(a: __m256i, b: __m256i) -> __m256i {
+    _mm256_alignr_epi8(_mm256_permute2x128_si256(a, b, 0x21), a, 8)
+}
+
+/// Computes the next 4 words of the message schedule from the previous 16
+/// words stored in `v0..v3`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn schedule(v0: __m256i, v1: __m256i, v2: __m256i, v3: __m256i) -> __m256i {
+    // W[t - 16] + sigma0(W[t - 15]) + W[t - 7]
+    let t = _mm256_add_epi64(_mm256_add_epi64(v0, sigma0(shift1(v0, v1))), shift1(v2, v3));
+    // sigma1(W[t - 2]) is available only for the lower two words, the upper
+    // two depend on the words computed in the first step
+    let lo = _mm256_add_epi64(t, sigma1(_mm256_permute2x128_si256(v3, v3, 0x81)));
+    _mm256_add_epi64(lo, sigma1(_mm256_permute2x128_si256(lo, lo, 0x08)))
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn digest_blocks(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
+    let mask = _mm256_set_epi64x(
+        0x0809_0a0b_0c0d_0e0f,
+        0x0001_0203_0405_0607,
+        0x0809_0a0b_0c0d_0e0f,
+        0x0001_0203_0405_0607,
+    );
+
+    let mut wk = [0u64; 80];
+    for block in blocks {
+        let ptr = block.as_ptr() as *const __m256i;
+        let mut w = [
+            _mm256_shuffle_epi8(_mm256_loadu_si256(ptr), mask),
+            _mm256_shuffle_epi8(_mm256_loadu_si256(ptr.add(1)), mask),
+            _mm256_shuffle_epi8(_mm256_loadu_si256(ptr.add(2)), mask),
+            _mm256_shuffle_epi8(_mm256_loadu_si256(ptr.add(3)), mask),
+        ];
+
+        for i in 0..20 {
+            if i >= 4 {
+                w[i % 4] = schedule(w[i % 4], w[(i + 1) % 4], w[(i + 2) % 4], w[(i + 3) % 4]);
+            }
+            let k = _mm256_loadu_si256(K64[4 * i..].as_ptr() as *const __m256i);
+            let dst = wk[4 * i..].as_mut_ptr() as *mut __m256i;
+            _mm256_storeu_si256(dst, _mm256_add_epi64(w[i % 4], k));
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for wk in wk.iter() {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(*wk);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+            *s = s.wrapping_add(*v);
+        }
+    }
+}
+
+pub fn detect() -> Backend {
+    // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
+    // after stabilization
+    if cpuid_bool::cpuid_bool!("avx2") {
+        Backend::Avx2
+    } else {
+        Backend::Soft
+    }
+}
+
+pub fn is_supported(backend: Backend) -> bool {
+    match backend {
+        Backend::Soft => true,
+        Backend::Avx2 => cpuid_bool::cpuid_bool!("avx2"),
+        _ => false,
+    }
+}
+
+pub fn compress(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
+    if super::backend() == Backend::Avx2 {
+        unsafe {
+            digest_blocks(state, blocks);
+        }
+    } else {
+        super::soft::compress(state, blocks);
+    }
+}
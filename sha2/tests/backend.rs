@@ -0,0 +1,73 @@
+use digest::Digest;
+use sha2::backend::{self, Backend, UnsupportedBackend};
+use sha2::{Sha256, Sha512};
+
+const BACKENDS: [Backend; 6] = [
+    Backend::Soft,
+    Backend::ShaNi,
+    Backend::Avx2,
+    Backend::Ssse3,
+    Backend::Asm,
+    Backend::Aarch64,
+];
+
+// the selection is global, so all checks are done in a single test
+#[test]
+fn force_backend() {
+    let msg: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+    let expected256 = Sha256::digest(&msg);
+    let expected512 = Sha512::digest(&msg);
+    let default256 = backend::sha256();
+    let default512 = backend::sha512();
+
+    for &b in BACKENDS.iter() {
+        match backend::force_sha256(Some(b)) {
+            Ok(()) => {
+                assert_eq!(backend::sha256(), b);
+                assert_eq!(Sha256::digest(&msg), expected256);
+
+                let inputs = [&msg[..], &msg[..]];
+                let mut outputs = [Default::default(); 2];
+                Sha256::digest_many_into(&inputs, &mut outputs);
+                assert_eq!(outputs, [expected256, expected256]);
+
+                let (left, right) = ([1u8; 32], [2u8; 32]);
+                let mut pair = [1u8; 64];
+                pair[32..].copy_from_slice(&right);
+                assert_eq!(
+                    Sha256::hash_pair(&left, &right)[..],
+                    Sha256::digest(&pair)[..]
+                );
+            }
+            Err(UnsupportedBackend) => assert_eq!(backend::sha256(), default256),
+        }
+
+        match backend::force_sha512(Some(b)) {
+            Ok(()) => {
+                assert_eq!(backend::sha512(), b);
+                assert_eq!(Sha512::digest(&msg), expected512);
+            }
+            Err(UnsupportedBackend) => assert_eq!(backend::sha512(), default512),
+        }
+
+        backend::force_sha256(None).unwrap();
+        backend::force_sha512(None).unwrap();
+    }
+
+    assert_eq!(backend::sha256(), default256);
+    assert_eq!(backend::sha512(), default512);
+    assert!(backend::force_sha256(Some(Backend::Soft)).is_ok());
+    assert!(backend::force_sha512(Some(Backend::Soft)).is_ok());
+    backend::force_sha256(None).unwrap();
+    backend::force_sha512(None).unwrap();
+}
+
+#[test]
+fn shared_with_sha1() {
+    // the `Backend` discriminants are only comparable across the crates if
+    // the definitions stay identical
+    assert_eq!(
+        include_str!("../src/backend/shared.rs"),
+        include_str!("../../sha1/src/backend/shared.rs"),
+    );
+}
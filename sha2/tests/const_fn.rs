@@ -0,0 +1,66 @@
+#![cfg(feature = "const-fn")]
+use digest::Digest;
+use hex_literal::hex;
+use sha2::{Sha224, Sha256, Sha256_192, Sha384, Sha512, Sha512Trunc224, Sha512Trunc256};
+
+const SHA256_ABC: [u8; 32] = Sha256::const_digest(b"abc");
+const SHA384_EMPTY: [u8; 48] = Sha384::const_digest(b"");
+const SHA512_ABC: [u8; 64] = Sha512::const_digest(b"abc");
+
+#[test]
+fn const_items() {
+    assert_eq!(
+        SHA256_ABC,
+        hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+    assert_eq!(
+        SHA384_EMPTY[..],
+        hex!(
+            "
+            38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da
+            274edebfe76f65fbd51ad2f14898b95b
+        "
+        )[..]
+    );
+    assert_eq!(
+        SHA512_ABC[..],
+        hex!(
+            "
+            ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a
+            2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f
+        "
+        )[..]
+    );
+}
+
+#[test]
+fn sha256_const_digest() {
+    let msg: Vec<u8> = (0..300).map(|i| i as u8).collect();
+    for len in 0..msg.len() {
+        let msg = &msg[..len];
+        assert_eq!(Sha224::const_digest(msg)[..], Sha224::digest(msg)[..]);
+        assert_eq!(Sha256::const_digest(msg)[..], Sha256::digest(msg)[..]);
+        assert_eq!(
+            Sha256_192::const_digest(msg)[..],
+            Sha256_192::digest(msg)[..]
+        );
+    }
+}
+
+#[test]
+fn sha512_const_digest() {
+    let msg: Vec<u8> = (0..300).map(|i| i as u8).collect();
+    for len in 0..msg.len() {
+        let msg = &msg[..len];
+        assert_eq!(Sha384::const_digest(msg)[..], Sha384::digest(msg)[..]);
+        assert_eq!(Sha512::const_digest(msg)[..], Sha512::digest(msg)[..]);
+        assert_eq!(
+            Sha512Trunc224::const_digest(msg)[..],
+            Sha512Trunc224::digest(msg)[..]
+        );
+        assert_eq!(
+            Sha512Trunc256::const_digest(msg)[..],
+            Sha512Trunc256::digest(msg)[..]
+        );
+    }
+}
@@ -3,6 +3,8 @@ use digest::new_test;
 
 new_test!(sha224_main, "sha224", sha2::Sha224, digest_test);
 new_test!(sha256_main, "sha256", sha2::Sha256, digest_test);
+// the `sha256` test vectors truncated to 24 bytes, SP 800-208 has no vectors of its own
+new_test!(sha256_192_main, "sha256_192", sha2::Sha256_192, digest_test);
 new_test!(sha384_main, "sha384", sha2::Sha384, digest_test);
 new_test!(sha512_main, "sha512", sha2::Sha512, digest_test);
 new_test!(
@@ -29,3 +31,21 @@ fn sha512_1million_a() {
     let output = include_bytes!("data/sha512_one_million_a.bin");
     one_million_a::<sha2::Sha512>(output);
 }
+
+#[test]
+fn sha256_192_abc() {
+    use digest::Digest;
+    use hex_literal::hex;
+
+    // SP 800-208 uses the SHA-256 IV, so this is the FIPS 180-4 "abc"
+    // example truncated to 24 bytes, while SHA-224 uses a different IV
+    let expected = hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9c");
+    assert_eq!(sha2::Sha256_192::digest(b"abc")[..], expected[..]);
+    assert_ne!(sha2::Sha224::digest(b"abc")[..24], expected[..]);
+    for msg in &[&b""[..], &[0x61; 1000][..]] {
+        assert_ne!(
+            sha2::Sha256_192::digest(msg)[..],
+            sha2::Sha224::digest(msg)[..24]
+        );
+    }
+}
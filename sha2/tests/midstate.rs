@@ -0,0 +1,62 @@
+use digest::Digest;
+use sha2::{MidstateError, Sha224, Sha256, Sha384, Sha512, Sha512Trunc256};
+
+#[test]
+fn sha256_midstate() {
+    let msg: Vec<u8> = (0..300).map(|i| i as u8).collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update(&msg[..128]);
+    let (state, len) = hasher.midstate().unwrap();
+    assert_eq!(len, 128);
+
+    let mut resumed = Sha256::from_midstate(state, len).unwrap();
+    resumed.update(&msg[128..]);
+    assert_eq!(resumed.finalize(), Sha256::digest(&msg));
+
+    let mut hasher = Sha224::new();
+    hasher.update(&msg[..64]);
+    let (state, len) = hasher.midstate().unwrap();
+    let mut resumed = Sha224::from_midstate(state, len).unwrap();
+    resumed.update(&msg[64..]);
+    assert_eq!(resumed.finalize(), Sha224::digest(&msg));
+}
+
+#[test]
+fn sha512_midstate() {
+    let msg: Vec<u8> = (0..300).map(|i| i as u8).collect();
+
+    let mut hasher = Sha512::new();
+    hasher.update(&msg[..256]);
+    let (state, len) = hasher.midstate().unwrap();
+    assert_eq!(len, 256);
+    let mut resumed = Sha512::from_midstate(state, len).unwrap();
+    resumed.update(&msg[256..]);
+    assert_eq!(resumed.finalize(), Sha512::digest(&msg));
+
+    let mut hasher = Sha384::new();
+    hasher.update(&msg[..128]);
+    let (state, len) = hasher.midstate().unwrap();
+    let mut resumed = Sha384::from_midstate(state, len).unwrap();
+    resumed.update(&msg[128..]);
+    assert_eq!(resumed.finalize(), Sha384::digest(&msg));
+
+    let (state, len) = Sha512Trunc256::new().midstate().unwrap();
+    assert_eq!(len, 0);
+    let resumed = Sha512Trunc256::from_midstate(state, len).unwrap();
+    assert_eq!(resumed.finalize(), Sha512Trunc256::digest(b""));
+}
+
+#[test]
+fn midstate_unaligned() {
+    let mut hasher = Sha256::new();
+    hasher.update(&[0u8; 65][..]);
+    assert_eq!(hasher.midstate().unwrap_err(), MidstateError);
+    assert!(Sha256::from_midstate([0; 8], 65).is_err());
+    assert!(Sha256::from_midstate([0; 8], u64::MAX - 63).is_err());
+
+    let mut hasher = Sha512::new();
+    hasher.update(&[0u8; 64][..]);
+    assert!(hasher.midstate().is_err());
+    assert!(Sha512::from_midstate([0; 8], 64).is_err());
+}
@@ -0,0 +1,57 @@
+use digest::Digest;
+use sha2::Sha256;
+
+fn messages() -> Vec<Vec<u8>> {
+    // lengths around the padding boundaries, in a mixed order so that
+    // lanes finish at different times
+    [
+        0, 1, 55, 56, 63, 64, 65, 119, 120, 128, 1000, 3, 4096, 200, 5, 777, 64, 0,
+    ]
+    .iter()
+    .enumerate()
+    .map(|(j, &n)| (0..n).map(|i| (i * 31 + j) as u8).collect())
+    .collect()
+}
+
+#[test]
+fn digest_many_into() {
+    let msgs = messages();
+    let inputs: Vec<&[u8]> = msgs.iter().map(|m| &m[..]).collect();
+    for count in 0..=inputs.len() {
+        let mut outputs = vec![Default::default(); count];
+        Sha256::digest_many_into(&inputs[..count], &mut outputs);
+        for (out, msg) in outputs.iter().zip(inputs.iter()) {
+            assert_eq!(*out, Sha256::digest(msg));
+        }
+    }
+}
+
+#[test]
+fn hash_many() {
+    let msgs = messages();
+    let inputs: Vec<&[u8]> = msgs.iter().map(|m| &m[..]).collect();
+    let mut outputs = vec![Default::default(); inputs.len()];
+    Sha256::hash_many(&inputs, &mut outputs);
+    for (out, msg) in outputs.iter().zip(inputs.iter()) {
+        assert_eq!(*out, Sha256::digest(msg));
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn digest_many() {
+    let msgs = messages();
+    let inputs: Vec<&[u8]> = msgs.iter().map(|m| &m[..]).collect();
+    let outputs = Sha256::digest_many(&inputs);
+    assert_eq!(outputs.len(), inputs.len());
+    for (out, msg) in outputs.iter().zip(inputs.iter()) {
+        assert_eq!(*out, Sha256::digest(msg));
+    }
+}
+
+#[test]
+#[should_panic]
+fn digest_many_into_length_mismatch() {
+    let mut outputs = vec![Default::default(); 1];
+    Sha256::digest_many_into(&[b"a", b"b"], &mut outputs);
+}
@@ -0,0 +1,48 @@
+use digest::Digest;
+use hex_literal::hex;
+use sha2::Sha256;
+
+fn node(j: u8) -> [u8; 32] {
+    let mut res = [0u8; 32];
+    for (i, v) in res.iter_mut().enumerate() {
+        *v = (i as u8).wrapping_mul(31).wrapping_add(j);
+    }
+    res
+}
+
+#[test]
+fn hash_pair() {
+    assert_eq!(
+        Sha256::hash_pair(&[0; 32], &[0; 32]),
+        hex!("f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b")
+    );
+
+    for j in 0..16 {
+        let (left, right) = (node(2 * j), node(2 * j + 1));
+        let mut msg = [0u8; 64];
+        msg[..32].copy_from_slice(&left);
+        msg[32..].copy_from_slice(&right);
+        assert_eq!(
+            Sha256::hash_pair(&left, &right)[..],
+            Sha256::digest(&msg)[..]
+        );
+    }
+}
+
+#[test]
+fn hash_pairs() {
+    let pairs: Vec<[[u8; 32]; 2]> = (0..16).map(|j| [node(2 * j), node(2 * j + 1)]).collect();
+    let mut outputs = vec![[0u8; 32]; pairs.len()];
+    Sha256::hash_pairs(&pairs, &mut outputs);
+    for (pair, out) in pairs.iter().zip(outputs.iter()) {
+        assert_eq!(*out, Sha256::hash_pair(&pair[0], &pair[1]));
+    }
+}
+
+#[test]
+#[should_panic]
+fn hash_pairs_length_mismatch() {
+    let pairs = [[[0u8; 32]; 2]; 2];
+    let mut outputs = [[0u8; 32]; 1];
+    Sha256::hash_pairs(&pairs, &mut outputs);
+}
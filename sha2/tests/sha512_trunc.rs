@@ -0,0 +1,78 @@
+use digest::consts::{U20, U28, U32, U48};
+use digest::{Digest, Reset, Update, VariableOutput};
+use hex_literal::hex;
+use sha2::{Sha512Trunc, Sha512Trunc224, Sha512Trunc256, Sha512TruncFixed};
+
+fn sha512_t(t: usize, msg: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha512Trunc::with_bits(t).unwrap();
+    hasher.update(msg);
+    let mut res = Vec::new();
+    hasher.finalize_variable(|out| res.extend_from_slice(out));
+    res
+}
+
+#[test]
+fn sha512_trunc_matches_precomputed() {
+    let msg = b"The quick brown fox jumps over the lazy dog";
+    assert_eq!(sha512_t(224, msg), Sha512Trunc224::digest(msg).to_vec());
+    assert_eq!(sha512_t(256, msg), Sha512Trunc256::digest(msg).to_vec());
+    assert_eq!(
+        Sha512TruncFixed::<U28>::digest(msg),
+        Sha512Trunc224::digest(msg)
+    );
+    assert_eq!(
+        Sha512TruncFixed::<U32>::digest(msg),
+        Sha512Trunc256::digest(msg)
+    );
+}
+
+#[test]
+fn sha512_trunc() {
+    assert_eq!(sha512_t(8, b"abc"), hex!("c5"));
+    assert_eq!(sha512_t(12, b"abc"), hex!("1ac0"));
+    assert_eq!(sha512_t(72, b"abc"), hex!("644d768d5298864595"));
+    assert_eq!(
+        sha512_t(160, b"abc"),
+        hex!("0a74fe1b43eecbea62182658da8a68b8acef25bf")
+    );
+    assert_eq!(
+        Sha512TruncFixed::<U20>::digest(b"abc")[..],
+        hex!("0a74fe1b43eecbea62182658da8a68b8acef25bf")[..]
+    );
+    assert_eq!(
+        sha512_t(511, b"abc")[..],
+        hex!(
+            "
+            71a80c6a46fbd2d092522f3a5d7750b9daa2c59f2ff05dfde25cd68e53317f4e
+            79a080da3d4145b3fc2d8fe520cd787da4bb0165a90296a99a9a9b87994a087c
+        "
+        )[..]
+    );
+
+    let mut hasher = Sha512Trunc::new(25).unwrap();
+    assert_eq!(hasher.output_size(), 25);
+    hasher.update(b"garbage");
+    hasher.reset();
+    hasher.update(b"abc");
+    hasher.finalize_variable(|out| {
+        assert_eq!(
+            out,
+            hex!("2c199c1b8e934d616332dcfea4d50a1ddbbb8eb25be46bdc9d")
+        );
+    });
+}
+
+#[test]
+fn sha512_trunc_invalid_size() {
+    assert!(Sha512Trunc::with_bits(0).is_err());
+    assert!(Sha512Trunc::with_bits(384).is_err());
+    assert!(Sha512Trunc::with_bits(512).is_err());
+    assert!(Sha512Trunc::new(48).is_err());
+    assert!(Sha512Trunc::new(64).is_err());
+}
+
+#[test]
+#[should_panic]
+fn sha512_trunc_fixed_invalid_size() {
+    Sha512TruncFixed::<U48>::new();
+}
@@ -0,0 +1,47 @@
+//! Encoding functions defined in NIST SP 800-185, section 2.3.
+
+/// Encodes `x` as a byte string which starts with its own length.
+pub(crate) fn left_encode(x: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = num_bytes(x);
+    buf[0] = n as u8;
+    buf[1..=n].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+    &buf[..=n]
+}
+
+/// Encodes `x` as a byte string which ends with its own length.
+pub(crate) fn right_encode(x: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = num_bytes(x);
+    buf[..n].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+    buf[n] = n as u8;
+    &buf[..=n]
+}
+
+/// Feeds `bytepad(encode_string(s_1) || ... || encode_string(s_k), rate)`
+/// into `f`, so the total number of bytes passed is a multiple of `rate`.
+pub(crate) fn bytepad_strings(rate: usize, strings: &[&[u8]], mut f: impl FnMut(&[u8])) {
+    let mut buf = [0u8; 9];
+    let mut n = 0;
+
+    let w = left_encode(rate as u64, &mut buf);
+    n += w.len();
+    f(w);
+    for s in strings {
+        let l = left_encode((s.len() as u64).wrapping_mul(8), &mut buf);
+        n += l.len() + s.len();
+        f(l);
+        f(s);
+    }
+
+    let zeros = [0u8; 32];
+    let mut pad = (rate - n % rate) % rate;
+    while pad > 0 {
+        let k = core::cmp::min(pad, zeros.len());
+        f(&zeros[..k]);
+        pad -= k;
+    }
+}
+
+fn num_bytes(x: u64) -> usize {
+    let n = 8 - (x.leading_zeros() / 8) as usize;
+    core::cmp::max(n, 1)
+}
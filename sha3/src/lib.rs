@@ -8,6 +8,22 @@
 //! * `SHA3-512`
 //! * `SHAKE128`, an extendable output function (XOF)
 //! * `SHAKE256`, an extendable output function (XOF)
+//!
+//! Additionally this crate implements:
+//!
+//! * `cSHAKE128`, `cSHAKE256`, customizable SHAKE defined in NIST SP 800-185
+//! * `KMAC128`, `KMAC256`, Keccak message authentication code defined in
+//!   NIST SP 800-185, including the `KMACXOF` variants
+//! * `TupleHash128`, `TupleHash256`, `ParallelHash128`, `ParallelHash256`
+//!   defined in NIST SP 800-185, with fixed output and XOF variants. With
+//!   the `rayon` feature the blocks of ParallelHash in large updates are
+//!   hashed on multiple threads
+//! * `KeccakSponge`, a low-level sponge and duplex object with arbitrary rate
+//! * `Shake128Fixed`, `Shake256Fixed`, SHAKE with a fixed output size which
+//!   implements `Digest`, e.g. `Shake256_912` used by Ed448 and
+//!   `Shake256_192` defined in NIST SP 800-208
+//! * `Shake128Rng`, `Shake256Rng`, deterministic random bit generators
+//!   implementing `rand_core` traits (requires the `rand_core` feature)
 //! * `Keccak224`, `Keccak256`, `Keccak384`, `Keccak512` (NIST submission
 //!    without padding changes)
 //!
@@ -51,6 +67,34 @@
 //! assert_eq!(res1, hex!("5881092dd818bf5cf8a3"));
 //! ```
 //!
+//! KMAC implements the `Mac` and `NewMac` traits from the `crypto-mac`
+//! crate. The XOF variant is available via the `finalize_xof` method:
+//!
+//! ```
+//! use sha3::Kmac128;
+//! use sha3::crypto_mac::{Mac, NewMac};
+//! use sha3::digest::XofReader;
+//!
+//! let mut mac = Kmac128::new_varkey(b"my key").unwrap();
+//! mac.update(b"hello world");
+//! let code = mac.clone().finalize().into_bytes();
+//!
+//! let mut reader = mac.finalize_xof();
+//! let mut buf = [0u8; 42];
+//! reader.read(&mut buf);
+//! ```
+//!
+//! Many independent messages can be hashed at once with the batch methods,
+//! which use a four-way interleaved permutation on CPUs with AVX2 support:
+//!
+//! ```
+//! use sha3::{Digest, Sha3_256};
+//!
+//! let msgs: [&[u8]; 4] = [b"a", b"bc", b"def", b""];
+//! let hashes = Sha3_256::digest_x4(msgs);
+//! assert_eq!(hashes[1], Sha3_256::digest(b"bc"));
+//! ```
+//!
 //! Also see [RustCrypto/hashes][2] readme.
 //!
 //! [1]: https://en.wikipedia.org/wiki/SHA-3
@@ -67,20 +111,33 @@
 #[cfg(feature = "std")]
 extern crate std;
 
+pub use crypto_mac;
 pub use digest::{self, Digest};
+#[cfg(feature = "rand_core")]
+pub use rand_core;
 
 use block_buffer::BlockBuffer;
-use digest::consts::{U104, U136, U144, U168, U200, U28, U32, U48, U64, U72};
-use digest::generic_array::typenum::Unsigned;
-use digest::{BlockInput, ExtendableOutputDirty, FixedOutputDirty, Reset, Update};
+use core::{cmp, fmt, marker::PhantomData, num::NonZeroUsize};
+use crypto_mac::{InvalidKeyLength, Mac, NewMac};
+use digest::consts::{U104, U114, U136, U144, U168, U200, U24, U28, U32, U48, U64, U72};
+use digest::generic_array::{typenum::Unsigned, ArrayLength, GenericArray};
+use digest::{BlockInput, ExtendableOutputDirty, FixedOutputDirty, Reset, Update, XofReader};
 
+mod encoding;
 mod paddings;
 #[macro_use]
 mod macros;
 mod reader;
+#[cfg(feature = "rand_core")]
+mod rng;
+mod sponge;
 mod state;
+mod x4;
 
 pub use crate::reader::Sha3XofReader;
+#[cfg(feature = "rand_core")]
+pub use crate::rng::{Shake128Rng, Shake256Rng};
+pub use crate::sponge::KeccakSponge;
 use crate::state::Sha3State;
 
 sha3_impl!(
@@ -161,3 +218,125 @@ shake_impl!(
     paddings::Shake,
     "SHAKE256 extendable output (XOF) hash function"
 );
+
+shake_fixed_impl!(
+    Shake128Fixed,
+    Shake128,
+    U168,
+    "SHAKE128 with a fixed output size, usable via the `Digest` trait."
+);
+shake_fixed_impl!(
+    Shake256Fixed,
+    Shake256,
+    U136,
+    "SHAKE256 with a fixed output size, usable via the `Digest` trait."
+);
+
+/// SHAKE128 with 256-bit output, as used with RSASSA-PSS and ECDSA (RFC 8692).
+pub type Shake128_256 = Shake128Fixed<U32>;
+/// SHAKE256 with 512-bit output, as used with RSASSA-PSS and ECDSA (RFC 8692).
+pub type Shake256_512 = Shake256Fixed<U64>;
+/// SHAKE256 with 912-bit output, as used by Ed448 (RFC 8032).
+pub type Shake256_912 = Shake256Fixed<U114>;
+/// SHAKE256 with 192-bit output, as used by the `LMS_SHAKE_M24_*` parameter
+/// sets of LMS and the `SHAKE256_192` parameter sets of XMSS (NIST SP 800-208).
+pub type Shake256_192 = Shake256Fixed<U24>;
+
+cshake_impl!(
+    CShake128,
+    U168,
+    "cSHAKE128 customizable extendable output (XOF) hash function"
+);
+cshake_impl!(
+    CShake256,
+    U136,
+    "cSHAKE256 customizable extendable output (XOF) hash function"
+);
+
+kmac_impl!(
+    Kmac128,
+    CShake128,
+    U32,
+    "KMAC128 message authentication code with 256-bit output"
+);
+kmac_impl!(
+    Kmac256,
+    CShake256,
+    U64,
+    "KMAC256 message authentication code with 512-bit output"
+);
+
+tuple_hash_impl!(
+    TupleHash128,
+    CShake128,
+    b"TupleHash",
+    "TupleHash128 hash function for tuples of byte strings"
+);
+fixed_len_impl!(TupleHash128);
+tuple_hash_impl!(
+    TupleHash256,
+    CShake256,
+    b"TupleHash",
+    "TupleHash256 hash function for tuples of byte strings"
+);
+fixed_len_impl!(TupleHash256);
+tuple_hash_impl!(
+    TupleHashXof128,
+    CShake128,
+    b"TupleHashXOF",
+    "TupleHashXOF128 extendable output (XOF) hash function for tuples of byte strings"
+);
+xof_impl!(TupleHashXof128);
+tuple_hash_impl!(
+    TupleHashXof256,
+    CShake256,
+    b"TupleHashXOF",
+    "TupleHashXOF256 extendable output (XOF) hash function for tuples of byte strings"
+);
+xof_impl!(TupleHashXof256);
+
+/// Minimum length of input to `update` for which the blocks of ParallelHash
+/// are hashed on the rayon thread pool.
+#[cfg(feature = "rayon")]
+const PARALLEL_MIN_LEN: usize = 1 << 16;
+/// Maximum number of blocks of ParallelHash hashed at once on the rayon
+/// thread pool.
+#[cfg(feature = "rayon")]
+const PARALLEL_BATCH: usize = 64;
+
+parallel_hash_impl!(
+    ParallelHash128,
+    CShake128,
+    Shake128,
+    U32,
+    b"ParallelHash",
+    "ParallelHash128 hash function"
+);
+fixed_len_impl!(ParallelHash128);
+parallel_hash_impl!(
+    ParallelHash256,
+    CShake256,
+    Shake256,
+    U64,
+    b"ParallelHash",
+    "ParallelHash256 hash function"
+);
+fixed_len_impl!(ParallelHash256);
+parallel_hash_impl!(
+    ParallelHashXof128,
+    CShake128,
+    Shake128,
+    U32,
+    b"ParallelHashXOF",
+    "ParallelHashXOF128 extendable output (XOF) hash function"
+);
+xof_impl!(ParallelHashXof128);
+parallel_hash_impl!(
+    ParallelHashXof256,
+    CShake256,
+    Shake256,
+    U64,
+    b"ParallelHashXOF",
+    "ParallelHashXOF256 extendable output (XOF) hash function"
+);
+xof_impl!(ParallelHashXof256);
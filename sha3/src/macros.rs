@@ -58,6 +58,41 @@ macro_rules! sha3_impl {
             }
         }
 
+        impl $state {
+            /// Compute hashes of four messages at once.
+            ///
+            /// On CPUs with AVX2 support the messages are processed by
+            /// a four-way interleaved Keccak-f\[1600\] permutation.
+            pub fn digest_x4(inputs: [&[u8]; 4]) -> [digest::Output<Self>; 4] {
+                Self::digest_batch(&inputs)
+            }
+
+            /// Compute hashes of an arbitrary number of messages, processing
+            /// them in groups of four as [`digest_x4`][Self::digest_x4] does.
+            #[cfg(feature = "std")]
+            pub fn digest_many(inputs: &[&[u8]]) -> std::vec::Vec<digest::Output<Self>> {
+                let mut res = std::vec::Vec::with_capacity(inputs.len());
+                for chunk in inputs.chunks(4) {
+                    let out = Self::digest_batch(chunk);
+                    res.extend_from_slice(&out[..chunk.len()]);
+                }
+                res
+            }
+
+            fn digest_batch(inputs: &[&[u8]]) -> [digest::Output<Self>; 4] {
+                let r = $rate::to_usize();
+                let states = x4::absorb_x4(r, <$padding>::DOMAIN, inputs);
+                let mut res: [digest::Output<Self>; 4] = Default::default();
+                for (out, state) in res.iter_mut().zip(states.iter()) {
+                    let n = out.len();
+                    state.as_bytes(|state| {
+                        out.copy_from_slice(&state[..n]);
+                    });
+                }
+                res
+            }
+        }
+
         opaque_debug::implement!($state);
         digest::impl_write!($state);
     };
@@ -90,7 +125,457 @@ macro_rules! shake_impl {
             }
         }
 
+        impl $state {
+            /// Absorb four messages at once and return an XOF reader for
+            /// each of them.
+            ///
+            /// On CPUs with AVX2 support the messages are processed by
+            /// a four-way interleaved Keccak-f\[1600\] permutation.
+            pub fn digest_xof_x4(inputs: [&[u8]; 4]) -> [Sha3XofReader; 4] {
+                let r = $rate::to_usize();
+                let s = x4::absorb_x4(r, <$padding>::DOMAIN, &inputs);
+                [
+                    Sha3XofReader::new(s[0].clone(), r),
+                    Sha3XofReader::new(s[1].clone(), r),
+                    Sha3XofReader::new(s[2].clone(), r),
+                    Sha3XofReader::new(s[3].clone(), r),
+                ]
+            }
+
+            /// Absorb an arbitrary number of messages in groups of four as
+            /// [`digest_xof_x4`][Self::digest_xof_x4] does and return an XOF
+            /// reader for each of them.
+            #[cfg(feature = "std")]
+            pub fn digest_xof_many(inputs: &[&[u8]]) -> std::vec::Vec<Sha3XofReader> {
+                let r = $rate::to_usize();
+                let mut res = std::vec::Vec::with_capacity(inputs.len());
+                for chunk in inputs.chunks(4) {
+                    let s = x4::absorb_x4(r, <$padding>::DOMAIN, chunk);
+                    for state in s[..chunk.len()].iter() {
+                        res.push(Sha3XofReader::new(state.clone(), r));
+                    }
+                }
+                res
+            }
+        }
+
+        opaque_debug::implement!($state);
+        digest::impl_write!($state);
+    };
+}
+
+macro_rules! cshake_impl {
+    ($state:ident, $rate:ident, $doc:expr) => {
+        #[derive(Clone)]
+        #[doc=$doc]
+        pub struct $state {
+            state: Sha3State,
+            buffer: BlockBuffer<$rate>,
+            initial_state: Sha3State,
+            shake: bool,
+        }
+
+        impl $state {
+            /// Creates a new instance with the given function name `N` and
+            /// customization string `S`.
+            ///
+            /// If both strings are empty the result is equal to plain SHAKE.
+            pub fn new(function_name: &[u8], customization: &[u8]) -> Self {
+                let mut res = Self {
+                    state: Default::default(),
+                    buffer: Default::default(),
+                    initial_state: Default::default(),
+                    shake: function_name.is_empty() && customization.is_empty(),
+                };
+                if !res.shake {
+                    res.absorb_bytepad_prefix(&[function_name, customization]);
+                }
+                res
+            }
+
+            /// Absorbs `bytepad(encode_string(s_1) || ... , rate)` and makes
+            /// the resulting state the one restored on reset.
+            pub(crate) fn absorb_bytepad_prefix(&mut self, strings: &[&[u8]]) {
+                let r = $rate::to_usize();
+                encoding::bytepad_strings(r, strings, |b| self.absorb(b));
+                self.initial_state = self.state.clone();
+            }
+
+            fn absorb(&mut self, input: &[u8]) {
+                let s = &mut self.state;
+                self.buffer.input_block(input, |b| s.absorb_block(b));
+            }
+
+            fn apply_padding(&mut self) {
+                let buf = if self.shake {
+                    self.buffer.pad_with::<paddings::Shake>()
+                } else {
+                    self.buffer.pad_with::<paddings::CShake>()
+                };
+                let buf = buf.expect("we never use input_lazy");
+                self.state.absorb_block(buf);
+            }
+        }
+
+        impl Default for $state {
+            fn default() -> Self {
+                Self::new(&[], &[])
+            }
+        }
+
+        impl Update for $state {
+            fn update(&mut self, input: impl AsRef<[u8]>) {
+                self.absorb(input.as_ref())
+            }
+        }
+
+        impl ExtendableOutputDirty for $state {
+            type Reader = Sha3XofReader;
+
+            fn finalize_xof_dirty(&mut self) -> Sha3XofReader {
+                self.apply_padding();
+                let r = $rate::to_usize();
+                Sha3XofReader::new(self.state.clone(), r)
+            }
+        }
+
+        impl Reset for $state {
+            fn reset(&mut self) {
+                self.state = self.initial_state.clone();
+                self.buffer.reset();
+            }
+        }
+
         opaque_debug::implement!($state);
         digest::impl_write!($state);
     };
 }
+
+macro_rules! kmac_impl {
+    ($state:ident, $cshake:ident, $output_size:ident, $doc:expr) => {
+        #[derive(Clone)]
+        #[doc=$doc]
+        pub struct $state {
+            cshake: $cshake,
+        }
+
+        impl $state {
+            /// Creates a new KMAC instance with the given key and
+            /// customization string `S`.
+            pub fn new_customized(key: &[u8], customization: &[u8]) -> Self {
+                let mut cshake = $cshake::new(b"KMAC", customization);
+                cshake.absorb_bytepad_prefix(&[key]);
+                Self { cshake }
+            }
+
+            /// Finalizes KMAC with the requested output length, which is
+            /// bound into the result, and writes it into `out`.
+            ///
+            /// **WARNING!** Make sure to compare codes in constant time!
+            pub fn finalize_into(mut self, out: &mut [u8]) {
+                self.finalize_with_len(out.len() as u64);
+                self.cshake.finalize_xof_dirty().read(out);
+            }
+
+            /// Finalizes the KMACXOF variant, which does not bind the output
+            /// length, and returns a reader for output of arbitrary length.
+            pub fn finalize_xof(mut self) -> Sha3XofReader {
+                self.finalize_with_len(0);
+                self.cshake.finalize_xof_dirty()
+            }
+
+            fn finalize_with_len(&mut self, len: u64) {
+                let mut buf = [0u8; 9];
+                let encoded = encoding::right_encode(len.wrapping_mul(8), &mut buf);
+                self.cshake.update(encoded);
+            }
+        }
+
+        impl NewMac for $state {
+            type KeySize = $output_size;
+
+            fn new(key: &GenericArray<u8, $output_size>) -> Self {
+                Self::new_customized(key, &[])
+            }
+
+            fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+                Ok(Self::new_customized(key, &[]))
+            }
+        }
+
+        impl Mac for $state {
+            type OutputSize = $output_size;
+
+            fn update(&mut self, data: &[u8]) {
+                self.cshake.update(data);
+            }
+
+            fn reset(&mut self) {
+                self.cshake.reset();
+            }
+
+            fn finalize(mut self) -> crypto_mac::Output<Self> {
+                let mut out = GenericArray::default();
+                self.finalize_with_len($output_size::to_u64());
+                self.cshake.finalize_xof_dirty().read(&mut out);
+                crypto_mac::Output::new(out)
+            }
+        }
+
+        opaque_debug::implement!($state);
+        crypto_mac::impl_write!($state);
+    };
+}
+
+macro_rules! tuple_hash_impl {
+    ($state:ident, $cshake:ident, $function_name:expr, $doc:expr) => {
+        #[derive(Clone)]
+        #[doc=$doc]
+        pub struct $state {
+            cshake: $cshake,
+        }
+
+        impl $state {
+            /// Creates a new instance with the given customization string `S`.
+            pub fn new(customization: &[u8]) -> Self {
+                let cshake = $cshake::new($function_name, customization);
+                Self { cshake }
+            }
+
+            /// Absorbs the next element of the hashed tuple.
+            pub fn update_element(&mut self, element: &[u8]) {
+                let mut buf = [0u8; 9];
+                let len = (element.len() as u64).wrapping_mul(8);
+                self.cshake.update(encoding::left_encode(len, &mut buf));
+                self.cshake.update(element);
+            }
+
+            fn finalize_with_len(&mut self, len: u64) {
+                let mut buf = [0u8; 9];
+                let encoded = encoding::right_encode(len.wrapping_mul(8), &mut buf);
+                self.cshake.update(encoded);
+            }
+        }
+
+        impl Reset for $state {
+            fn reset(&mut self) {
+                self.cshake.reset();
+            }
+        }
+
+        opaque_debug::implement!($state);
+    };
+}
+
+macro_rules! parallel_hash_impl {
+    (
+        $state:ident, $cshake:ident, $leaf:ident, $cv_size:ident,
+        $function_name:expr, $doc:expr
+    ) => {
+        #[derive(Clone)]
+        #[doc=$doc]
+        pub struct $state {
+            cshake: $cshake,
+            leaf: $leaf,
+            block_size: usize,
+            leaf_pos: usize,
+            blocks: u64,
+        }
+
+        impl $state {
+            /// Creates a new instance with the given customization string `S`
+            /// and block size `B` in bytes.
+            pub fn new(customization: &[u8], block_size: NonZeroUsize) -> Self {
+                let mut res = Self {
+                    cshake: $cshake::new($function_name, customization),
+                    leaf: Default::default(),
+                    block_size: block_size.get(),
+                    leaf_pos: 0,
+                    blocks: 0,
+                };
+                res.absorb_block_size();
+                res
+            }
+
+            fn absorb_block_size(&mut self) {
+                let mut buf = [0u8; 9];
+                let b = encoding::left_encode(self.block_size as u64, &mut buf);
+                self.cshake.update(b);
+            }
+
+            fn absorb(&mut self, mut input: &[u8]) {
+                while !input.is_empty() {
+                    #[cfg(feature = "rayon")]
+                    {
+                        if self.leaf_pos == 0 && input.len() >= PARALLEL_MIN_LEN {
+                            input = self.absorb_parallel(input);
+                            continue;
+                        }
+                    }
+
+                    let n = cmp::min(self.block_size - self.leaf_pos, input.len());
+                    let (l, r) = input.split_at(n);
+                    input = r;
+                    self.leaf.update(l);
+                    self.leaf_pos += n;
+                    if self.leaf_pos == self.block_size {
+                        self.finish_leaf();
+                    }
+                }
+            }
+
+            /// Hashes up to `PARALLEL_BATCH` whole blocks of `input` on the
+            /// rayon thread pool and returns the rest of `input`.
+            #[cfg(feature = "rayon")]
+            fn absorb_parallel<'a>(&mut self, input: &'a [u8]) -> &'a [u8] {
+                use rayon::prelude::*;
+
+                let n = cmp::min(input.len() / self.block_size, PARALLEL_BATCH);
+                let (blocks, rest) = input.split_at(n * self.block_size);
+                let mut cvs = [GenericArray::<u8, $cv_size>::default(); PARALLEL_BATCH];
+                blocks
+                    .par_chunks(self.block_size)
+                    .zip(cvs[..n].par_iter_mut())
+                    .for_each(|(block, cv)| {
+                        let mut leaf = $leaf::default();
+                        leaf.update(block);
+                        leaf.finalize_xof_dirty().read(cv);
+                    });
+
+                for cv in cvs[..n].iter() {
+                    self.cshake.update(cv);
+                }
+                self.blocks += n as u64;
+                rest
+            }
+
+            fn finish_leaf(&mut self) {
+                let mut cv = GenericArray::<u8, $cv_size>::default();
+                self.leaf.finalize_xof_dirty().read(&mut cv);
+                self.leaf.reset();
+                self.cshake.update(&cv);
+                self.leaf_pos = 0;
+                self.blocks += 1;
+            }
+
+            fn finalize_with_len(&mut self, len: u64) {
+                if self.leaf_pos != 0 {
+                    self.finish_leaf();
+                }
+                let mut buf = [0u8; 9];
+                self.cshake.update(encoding::right_encode(self.blocks, &mut buf));
+                let encoded = encoding::right_encode(len.wrapping_mul(8), &mut buf);
+                self.cshake.update(encoded);
+            }
+        }
+
+        impl Update for $state {
+            fn update(&mut self, input: impl AsRef<[u8]>) {
+                self.absorb(input.as_ref())
+            }
+        }
+
+        impl Reset for $state {
+            fn reset(&mut self) {
+                self.cshake.reset();
+                self.leaf.reset();
+                self.leaf_pos = 0;
+                self.blocks = 0;
+                self.absorb_block_size();
+            }
+        }
+
+        opaque_debug::implement!($state);
+        digest::impl_write!($state);
+    };
+}
+
+macro_rules! fixed_len_impl {
+    ($state:ident) => {
+        impl $state {
+            /// Finalizes the hash with the requested output length, which is
+            /// bound into the result, and writes it into `out`.
+            pub fn finalize_into(mut self, out: &mut [u8]) {
+                self.finalize_with_len(out.len() as u64);
+                self.cshake.finalize_xof_dirty().read(out);
+            }
+        }
+    };
+}
+
+macro_rules! xof_impl {
+    ($state:ident) => {
+        impl ExtendableOutputDirty for $state {
+            type Reader = Sha3XofReader;
+
+            fn finalize_xof_dirty(&mut self) -> Sha3XofReader {
+                self.finalize_with_len(0);
+                self.cshake.finalize_xof_dirty()
+            }
+        }
+    };
+}
+
+macro_rules! shake_fixed_impl {
+    ($name:ident, $shake:ident, $rate:ident, $doc:expr) => {
+        #[doc = $doc]
+        ///
+        /// The output size `N` is given in bytes.
+        #[derive(Clone)]
+        pub struct $name<N: ArrayLength<u8>> {
+            shake: $shake,
+            _output_size: PhantomData<N>,
+        }
+
+        impl<N: ArrayLength<u8>> Default for $name<N> {
+            fn default() -> Self {
+                Self {
+                    shake: Default::default(),
+                    _output_size: PhantomData,
+                }
+            }
+        }
+
+        impl<N: ArrayLength<u8>> BlockInput for $name<N> {
+            type BlockSize = $rate;
+        }
+
+        impl<N: ArrayLength<u8>> Update for $name<N> {
+            fn update(&mut self, input: impl AsRef<[u8]>) {
+                self.shake.update(input)
+            }
+        }
+
+        impl<N: ArrayLength<u8>> FixedOutputDirty for $name<N> {
+            type OutputSize = N;
+
+            fn finalize_into_dirty(&mut self, out: &mut digest::Output<Self>) {
+                self.shake.finalize_xof_dirty().read(out);
+            }
+        }
+
+        impl<N: ArrayLength<u8>> Reset for $name<N> {
+            fn reset(&mut self) {
+                self.shake.reset();
+            }
+        }
+
+        impl<N: ArrayLength<u8>> fmt::Debug for $name<N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!(stringify!($name), " {{ ... }}"))
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl<N: ArrayLength<u8>> std::io::Write for $name<N> {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                Update::update(self, buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+    };
+}
@@ -7,6 +7,12 @@ macro_rules! impl_padding {
         #[derive(Copy, Clone, Default)]
         pub struct $name;
 
+        impl $name {
+            /// Domain separation bits followed by the first padding bit.
+            #[allow(dead_code)]
+            pub(crate) const DOMAIN: u8 = $pad;
+        }
+
         impl Padding for $name {
             #[inline(always)]
             fn pad_block(block: &mut [u8], pos: usize) -> Result<(), PadError> {
@@ -31,3 +37,4 @@ macro_rules! impl_padding {
 impl_padding!(Keccak, 0x01);
 impl_padding!(Sha3, 0x06);
 impl_padding!(Shake, 0x1f);
+impl_padding!(CShake, 0x04);
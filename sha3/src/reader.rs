@@ -4,20 +4,64 @@ use digest::XofReader;
 use std::io;
 
 /// Reader state for extracting extendable output.
+///
+/// Besides sequential reading, the reader supports jumping to an arbitrary
+/// output position via [`seek`][Self::seek] and [`skip`][Self::skip].
+/// Only the permutations needed to reach the target block are computed.
+#[derive(Clone)]
 pub struct Sha3XofReader {
     state: Sha3State,
     rate: usize,
     pos: usize,
+    block: u64,
+    initial_state: Sha3State,
 }
 
 impl Sha3XofReader {
     pub(crate) fn new(state: Sha3State, rate: usize) -> Self {
         Sha3XofReader {
+            initial_state: state.clone(),
             state,
             rate,
             pos: 0,
+            block: 0,
         }
     }
+
+    /// Current position in the output stream in bytes.
+    pub fn position(&self) -> u64 {
+        self.block * self.rate as u64 + self.pos as u64
+    }
+
+    /// Move to the given absolute position in the output stream, so the next
+    /// read starts with the output byte at offset `pos`.
+    ///
+    /// Seeking forward applies the permutation only once per skipped block.
+    /// Seeking backward has to restart from the beginning of the stream.
+    pub fn seek(&mut self, pos: u64) {
+        let rate = self.rate as u64;
+        let block = pos / rate;
+        if block < self.block {
+            self.state = self.initial_state.clone();
+            self.block = 0;
+        }
+        while self.block < block {
+            self.state.apply_f();
+            self.block += 1;
+        }
+        self.pos = (pos % rate) as usize;
+    }
+
+    /// Skip the next `n` bytes of the output stream.
+    pub fn skip(&mut self, n: u64) {
+        let pos = self.position().checked_add(n).expect("position overflow");
+        self.seek(pos);
+    }
+
+    fn apply_f(&mut self) {
+        self.state.apply_f();
+        self.block += 1;
+    }
 }
 
 impl XofReader for Sha3XofReader {
@@ -30,7 +74,7 @@ impl XofReader for Sha3XofReader {
             self.state.as_bytes(|state| {
                 l.copy_from_slice(&state[self.pos..self.rate]);
             });
-            self.state.apply_f();
+            self.apply_f();
         } else {
             self.state.as_bytes(|state| {
                 buffer.copy_from_slice(&state[self.pos..self.pos + n]);
@@ -46,7 +90,7 @@ impl XofReader for Sha3XofReader {
             self.state.as_bytes(|state| {
                 l.copy_from_slice(&state[..self.rate]);
             });
-            self.state.apply_f();
+            self.apply_f();
         }
 
         let n = buffer.len();
@@ -64,3 +108,24 @@ impl io::Read for Sha3XofReader {
         Ok(buf.len())
     }
 }
+
+#[cfg(feature = "std")]
+impl io::Seek for Sha3XofReader {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            io::SeekFrom::Start(n) => Some(n),
+            io::SeekFrom::Current(n) if n >= 0 => self.position().checked_add(n as u64),
+            io::SeekFrom::Current(n) => self.position().checked_sub(n.wrapping_neg() as u64),
+            io::SeekFrom::End(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "extendable output has no end",
+                ))
+            }
+        };
+        let target = target
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid seek position"))?;
+        Sha3XofReader::seek(self, target);
+        Ok(target)
+    }
+}
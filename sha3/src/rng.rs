@@ -0,0 +1,114 @@
+//! Deterministic random bit generators based on SHAKE.
+use crate::{Sha3XofReader, Shake128, Shake256};
+use digest::{ExtendableOutput, Update, XofReader};
+use rand_core::{impls, CryptoRng, Error, RngCore, SeedableRng};
+
+/// Domain separation tags prepended to the SHAKE input of each derivation.
+const TAG_SEED: u8 = 0x01;
+const TAG_RESEED: u8 = 0x02;
+const TAG_FORK: u8 = 0x03;
+
+macro_rules! shake_rng_impl {
+    ($name:ident, $shake:ident, $key_size:expr, $doc:expr) => {
+        #[doc = $doc]
+        ///
+        /// The generator output is read from a SHAKE XOF stream. Reseeding
+        /// and forking extract a key from the current stream and absorb it
+        /// together with the new input into a fresh SHAKE instance, so
+        /// compromise of the new state does not reveal previous outputs.
+        #[derive(Clone)]
+        pub struct $name {
+            reader: Sha3XofReader,
+        }
+
+        impl $name {
+            /// Create a new generator from a seed of arbitrary length.
+            pub fn new(seed: &[u8]) -> Self {
+                Self {
+                    reader: Self::derive(TAG_SEED, &[], seed),
+                }
+            }
+
+            /// Create a generator which returns exactly the output of
+            /// `reader`, e.g. to expand a seed as specified by a protocol.
+            pub fn from_reader(reader: Sha3XofReader) -> Self {
+                Self { reader }
+            }
+
+            /// Mix `entropy` into the state of the generator.
+            pub fn reseed(&mut self, entropy: &[u8]) {
+                let key = self.extract_key();
+                self.reader = Self::derive(TAG_RESEED, &key, entropy);
+            }
+
+            /// Derive an independent child generator bound to `label`.
+            ///
+            /// The state of `self` is advanced, so subsequent forks with
+            /// the same label return different generators.
+            pub fn fork(&mut self, label: &[u8]) -> Self {
+                let key = self.extract_key();
+                Self {
+                    reader: Self::derive(TAG_FORK, &key, label),
+                }
+            }
+
+            fn extract_key(&mut self) -> [u8; $key_size] {
+                let mut key = [0u8; $key_size];
+                self.reader.read(&mut key);
+                key
+            }
+
+            fn derive(tag: u8, key: &[u8], data: &[u8]) -> Sha3XofReader {
+                let mut shake = $shake::default();
+                shake.update(&[tag]);
+                shake.update(key);
+                shake.update(data);
+                shake.finalize_xof()
+            }
+        }
+
+        impl RngCore for $name {
+            fn next_u32(&mut self) -> u32 {
+                impls::next_u32_via_fill(self)
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                impls::next_u64_via_fill(self)
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                self.reader.read(dest);
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+
+        impl CryptoRng for $name {}
+
+        impl SeedableRng for $name {
+            type Seed = [u8; 32];
+
+            fn from_seed(seed: Self::Seed) -> Self {
+                Self::new(&seed)
+            }
+        }
+
+        opaque_debug::implement!($name);
+    };
+}
+
+shake_rng_impl!(
+    Shake128Rng,
+    Shake128,
+    32,
+    "Deterministic random bit generator based on SHAKE128."
+);
+shake_rng_impl!(
+    Shake256Rng,
+    Shake256,
+    64,
+    "Deterministic random bit generator based on SHAKE256."
+);
@@ -0,0 +1,146 @@
+use crate::state::{Sha3State, PLEN};
+use core::cmp;
+
+/// Keccak-f\[1600\] sponge and duplex object with a runtime-defined rate.
+///
+/// This is a low-level building block for constructions which are not
+/// covered by the hash functions in this crate, e.g. STROBE-style protocol
+/// transcripts. It provides no domain separation on its own, so it's up to
+/// the user to define an unambiguous encoding of the absorbed data.
+///
+/// Calls to [`absorb`][Self::absorb], [`pad`][Self::pad] and
+/// [`squeeze`][Self::squeeze] can be freely interleaved. For example,
+/// SHA3-256 corresponds to the following sequence of calls:
+///
+/// ```
+/// use sha3::{Digest, KeccakSponge, Sha3_256};
+///
+/// let mut sponge = KeccakSponge::new(136);
+/// sponge.absorb(b"abc");
+/// sponge.pad(0x06);
+/// let mut res = [0u8; 32];
+/// sponge.squeeze(&mut res);
+///
+/// assert_eq!(res[..], Sha3_256::digest(b"abc")[..]);
+/// ```
+#[derive(Clone)]
+pub struct KeccakSponge {
+    state: Sha3State,
+    rate: usize,
+    pos: usize,
+}
+
+impl KeccakSponge {
+    /// Create a new sponge with the given rate in bytes. The capacity is
+    /// equal to `200 - rate` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `rate` is equal to zero or not smaller than the 200 byte width of
+    /// the permutation.
+    pub fn new(rate: usize) -> Self {
+        assert!(rate > 0 && rate < 8 * PLEN, "invalid sponge rate");
+        Self {
+            state: Default::default(),
+            rate,
+            pos: 0,
+        }
+    }
+
+    /// Rate of the sponge in bytes.
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+
+    /// Capacity of the sponge in bytes.
+    pub fn capacity(&self) -> usize {
+        8 * PLEN - self.rate
+    }
+
+    /// Absorb `data` into the outer part of the state, applying the
+    /// permutation each time a full block is absorbed.
+    pub fn absorb(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            self.permute_if_full();
+            let n = cmp::min(self.rate - self.pos, data.len());
+            let (l, r) = data.split_at(n);
+            data = r;
+            self.state.xor_bytes(self.pos, l);
+            self.pos += n;
+        }
+    }
+
+    /// Apply the multi-rate `pad10*1` padding and permute the state.
+    ///
+    /// `domain` contains the domain separation bits followed by the first
+    /// padding bit, e.g. `0x06` for SHA-3, `0x1F` for SHAKE and `0x01` for
+    /// the original Keccak.
+    ///
+    /// # Panics
+    ///
+    /// If `domain` is equal to zero.
+    pub fn pad(&mut self, domain: u8) {
+        assert_ne!(domain, 0, "domain byte must contain the padding bit");
+        self.permute_if_full();
+        self.state.xor_bytes(self.pos, &[domain]);
+        if domain & 0x80 != 0 && self.pos == self.rate - 1 {
+            self.state.apply_f();
+        }
+        self.state.xor_bytes(self.rate - 1, &[0x80]);
+        self.permute();
+    }
+
+    /// Squeeze output from the outer part of the state, applying the
+    /// permutation each time a full block has been read.
+    pub fn squeeze(&mut self, mut out: &mut [u8]) {
+        while !out.is_empty() {
+            self.permute_if_full();
+            let n = cmp::min(self.rate - self.pos, out.len());
+            let (l, r) = { out }.split_at_mut(n);
+            out = r;
+            let pos = self.pos;
+            self.state.as_bytes(|state| {
+                l.copy_from_slice(&state[pos..pos + n]);
+            });
+            self.pos += n;
+        }
+    }
+
+    /// Perform a duplexing call: absorb `input` as a padded block and
+    /// squeeze `output` from the resulting state.
+    ///
+    /// This is equivalent to [`absorb`][Self::absorb] followed by
+    /// [`pad`][Self::pad] and [`squeeze`][Self::squeeze]. To follow the
+    /// duplex construction exactly, `input` must be shorter than the rate
+    /// and `output` must not be longer than the rate.
+    pub fn duplex(&mut self, input: &[u8], domain: u8, output: &mut [u8]) {
+        self.absorb(input);
+        self.pad(domain);
+        self.squeeze(output);
+    }
+
+    /// Apply the permutation and reset the position to the beginning of the
+    /// outer part of the state.
+    pub fn permute(&mut self) {
+        self.state.apply_f();
+        self.pos = 0;
+    }
+
+    /// Make the current state irreversible: permute it and overwrite its
+    /// outer part with zeros.
+    ///
+    /// This provides forward secrecy, i.e. compromise of the state after the
+    /// ratchet does not allow to recover previously absorbed data.
+    pub fn ratchet(&mut self) {
+        self.permute();
+        self.state.zero_prefix(self.rate);
+    }
+
+    fn permute_if_full(&mut self) {
+        if self.pos == self.rate {
+            self.permute();
+        }
+    }
+}
+
+opaque_debug::implement!(KeccakSponge);
@@ -1,6 +1,6 @@
 use core::convert::TryInto;
 
-const PLEN: usize = 25;
+pub(crate) const PLEN: usize = 25;
 
 #[derive(Clone, Default)]
 pub(crate) struct Sha3State {
@@ -52,6 +52,25 @@ impl Sha3State {
         f(data_ref);
     }
 
+    /// XORs `data` into the state starting at byte `offset`.
+    pub(crate) fn xor_bytes(&mut self, offset: usize, data: &[u8]) {
+        debug_assert!(offset + data.len() <= 8 * PLEN);
+        for (i, b) in data.iter().enumerate() {
+            let p = offset + i;
+            self.state[p / 8] ^= (*b as u64) << (8 * (p % 8));
+        }
+    }
+
+    /// Sets the first `n` bytes of the state to zero.
+    pub(crate) fn zero_prefix(&mut self, n: usize) {
+        debug_assert!(n <= 8 * PLEN);
+        let (full, rem) = (n / 8, n % 8);
+        self.state[..full].iter_mut().for_each(|v| *v = 0);
+        if rem != 0 {
+            self.state[full] &= !0u64 << (8 * rem);
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn apply_f(&mut self) {
         keccak::f1600(&mut self.state);
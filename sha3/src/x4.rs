@@ -0,0 +1,93 @@
+//! Four-way interleaved Keccak-f\[1600\] used for batch hashing.
+use crate::state::{Sha3State, PLEN};
+use core::convert::TryInto;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
+
+/// Four Keccak states stored lane by lane, i.e. `state[i][j]` is the i-th
+/// word of the j-th state.
+pub(crate) type State4 = [[u64; 4]; PLEN];
+
+/// Apply Keccak-f\[1600\] to each of the four interleaved states.
+///
+/// States for which `active` is `false` may be left unchanged.
+pub(crate) fn f1600x4(state: &mut State4, active: [bool; 4]) {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
+        // after stabilization
+        if cpuid_bool::cpuid_bool!("avx2") {
+            #[allow(unsafe_code)]
+            unsafe {
+                avx2::f1600x4(state);
+            }
+            return;
+        }
+    }
+    soft_f1600x4(state, active);
+}
+
+fn soft_f1600x4(state: &mut State4, active: [bool; 4]) {
+    for j in (0..4).filter(|&j| active[j]) {
+        let mut buf = [0u64; PLEN];
+        for (b, s) in buf.iter_mut().zip(state.iter()) {
+            *b = s[j];
+        }
+        keccak::f1600(&mut buf);
+        for (s, b) in state.iter_mut().zip(buf.iter()) {
+            s[j] = *b;
+        }
+    }
+}
+
+/// Absorb up to four messages in parallel and apply the final padding.
+///
+/// `rate` must be a multiple of 8. Returns the state of each sponge right
+/// after absorbing the padded last block of its message, which allows
+/// messages of different length to share the same permutation calls.
+/// States of missing lanes are left zeroed.
+pub(crate) fn absorb_x4(rate: usize, domain: u8, inputs: &[&[u8]]) -> [Sha3State; 4] {
+    debug_assert_eq!(rate % 8, 0);
+    debug_assert!(inputs.len() <= 4);
+    let mut state = [[0u64; 4]; PLEN];
+    let mut res: [Sha3State; 4] = Default::default();
+    let mut offsets = [0usize; 4];
+    let mut done = [true; 4];
+    done[..inputs.len()].iter_mut().for_each(|d| *d = false);
+
+    while done.iter().any(|d| !d) {
+        let mut last = [false; 4];
+        for j in (0..4).filter(|&j| !done[j]) {
+            let rem = &inputs[j][offsets[j]..];
+            if rem.len() >= rate {
+                xor_block(&mut state, j, &rem[..rate]);
+                offsets[j] += rate;
+            } else {
+                let mut block = [0u8; 8 * PLEN];
+                block[..rem.len()].copy_from_slice(rem);
+                block[rem.len()] ^= domain;
+                block[rate - 1] ^= 0x80;
+                xor_block(&mut state, j, &block[..rate]);
+                last[j] = true;
+            }
+        }
+
+        let active = [!done[0], !done[1], !done[2], !done[3]];
+        f1600x4(&mut state, active);
+
+        for j in (0..4).filter(|&j| last[j]) {
+            for (r, s) in res[j].state.iter_mut().zip(state.iter()) {
+                *r = s[j];
+            }
+            done[j] = true;
+        }
+    }
+    res
+}
+
+fn xor_block(state: &mut State4, j: usize, block: &[u8]) {
+    for (s, chunk) in state.iter_mut().zip(block.chunks_exact(8)) {
+        s[j] ^= u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+}
@@ -0,0 +1,103 @@
+//! AVX2 implementation of the four-way Keccak-f\[1600\] permutation.
+#![allow(unsafe_code)]
+
+use super::State4;
+use crate::state::PLEN;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+const RHO: [i64; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+const RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn rol(x: __m256i, n: i64) -> __m256i {
+    _mm256_or_si256(
+        _mm256_sllv_epi64(x, _mm256_set1_epi64x(n)),
+        _mm256_srlv_epi64(x, _mm256_set1_epi64x(64 - n)),
+    )
+}
+
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn f1600x4(state: &mut State4) {
+    let mut a = [_mm256_setzero_si256(); PLEN];
+    for (v, s) in a.iter_mut().zip(state.iter()) {
+        *v = _mm256_loadu_si256(s.as_ptr() as *const __m256i);
+    }
+
+    for rc in RC.iter() {
+        // Theta
+        let mut c = [_mm256_setzero_si256(); 5];
+        for (x, c) in c.iter_mut().enumerate() {
+            *c = _mm256_xor_si256(
+                _mm256_xor_si256(_mm256_xor_si256(a[x], a[x + 5]), a[x + 10]),
+                _mm256_xor_si256(a[x + 15], a[x + 20]),
+            );
+        }
+        for x in 0..5 {
+            let d = _mm256_xor_si256(c[(x + 4) % 5], rol(c[(x + 1) % 5], 1));
+            for y in 0..5 {
+                a[5 * y + x] = _mm256_xor_si256(a[5 * y + x], d);
+            }
+        }
+
+        // Rho and pi
+        let mut last = a[1];
+        for (&pi, &rho) in PI.iter().zip(RHO.iter()) {
+            let t = a[pi];
+            a[pi] = rol(last, rho);
+            last = t;
+        }
+
+        // Chi
+        for row in a.chunks_exact_mut(5) {
+            let mut r = [_mm256_setzero_si256(); 5];
+            r.copy_from_slice(row);
+            for (x, v) in row.iter_mut().enumerate() {
+                *v = _mm256_xor_si256(r[x], _mm256_andnot_si256(r[(x + 1) % 5], r[(x + 2) % 5]));
+            }
+        }
+
+        // Iota
+        a[0] = _mm256_xor_si256(a[0], _mm256_set1_epi64x(*rc as i64));
+    }
+
+    for (v, s) in a.iter().zip(state.iter_mut()) {
+        _mm256_storeu_si256(s.as_mut_ptr() as *mut __m256i, *v);
+    }
+}
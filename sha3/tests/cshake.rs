@@ -0,0 +1,93 @@
+use hex_literal::hex;
+use sha3::digest::{ExtendableOutput, Reset, Update, XofReader};
+use sha3::{CShake128, CShake256, Shake128};
+
+// Samples from NIST SP 800-185:
+// https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/cSHAKE_samples.pdf
+const S: &[u8] = b"Email Signature";
+
+fn msg200() -> [u8; 200] {
+    let mut buf = [0u8; 200];
+    for (i, b) in buf.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    buf
+}
+
+#[test]
+fn cshake128_nist_samples() {
+    let mut res = [0u8; 32];
+
+    let mut hasher = CShake128::new(b"", S);
+    hasher.update(hex!("00010203"));
+    hasher.finalize_xof().read(&mut res);
+    assert_eq!(
+        res,
+        hex!("c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5")
+    );
+
+    let mut hasher = CShake128::new(b"", S);
+    hasher.update(&msg200()[..]);
+    hasher.finalize_xof().read(&mut res);
+    assert_eq!(
+        res,
+        hex!("c5221d50e4f822d96a2e8881a961420f294b7b24fe3d2094baed2c6524cc166b")
+    );
+}
+
+#[test]
+fn cshake256_nist_samples() {
+    let mut res = [0u8; 64];
+
+    let mut hasher = CShake256::new(b"", S);
+    hasher.update(hex!("00010203"));
+    hasher.finalize_xof().read(&mut res);
+    assert_eq!(
+        res[..],
+        hex!("
+            d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd1
+            64020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c
+        ")[..]
+    );
+
+    let mut hasher = CShake256::new(b"", S);
+    hasher.update(&msg200()[..]);
+    hasher.finalize_xof().read(&mut res);
+    assert_eq!(
+        res[..],
+        hex!("
+            07dc27b11e51fbac75bc7b3c1d983e8b4b85fb1defaf218912ac864302730917
+            27f42b17ed1df63e8ec118f04b23633c1dfb1574c8fb55cb45da8e25afb092bb
+        ")[..]
+    );
+}
+
+#[test]
+fn cshake_empty_strings_is_shake() {
+    let mut expected = [0u8; 300];
+    let mut res = [0u8; 300];
+    Shake128::default()
+        .chain(b"abc")
+        .finalize_xof()
+        .read(&mut expected);
+    CShake128::new(b"", b"")
+        .chain(b"abc")
+        .finalize_xof()
+        .read(&mut res);
+    assert_eq!(res[..], expected[..]);
+}
+
+#[test]
+fn cshake_reset() {
+    let mut hasher = CShake128::new(b"", S);
+    hasher.update(b"garbage");
+    hasher.reset();
+    hasher.update(hex!("00010203"));
+
+    let mut res = [0u8; 32];
+    hasher.finalize_xof().read(&mut res);
+    assert_eq!(
+        res,
+        hex!("c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5")
+    );
+}
@@ -0,0 +1,175 @@
+use hex_literal::hex;
+use sha3::crypto_mac::{Mac, NewMac};
+use sha3::digest::XofReader;
+use sha3::{Kmac128, Kmac256};
+
+// Samples from NIST SP 800-185:
+// https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/KMAC_samples.pdf
+// https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/KMACXOF_samples.pdf
+const KEY: [u8; 32] = hex!("404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f");
+const S: &[u8] = b"My Tagged Application";
+
+fn msg200() -> [u8; 200] {
+    let mut buf = [0u8; 200];
+    for (i, b) in buf.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    buf
+}
+
+#[test]
+fn kmac128_nist_samples() {
+    let mut mac = Kmac128::new_varkey(&KEY).unwrap();
+    mac.update(&hex!("00010203"));
+    assert_eq!(
+        mac.finalize().into_bytes()[..],
+        hex!("e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e")[..]
+    );
+
+    let mut mac = Kmac128::new_customized(&KEY, S);
+    mac.update(&hex!("00010203"));
+    assert_eq!(
+        mac.finalize().into_bytes()[..],
+        hex!("3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5")[..]
+    );
+
+    let mut mac = Kmac128::new_customized(&KEY, S);
+    mac.update(&msg200());
+    assert_eq!(
+        mac.finalize().into_bytes()[..],
+        hex!("1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230")[..]
+    );
+}
+
+#[test]
+fn kmac256_nist_samples() {
+    let mut mac = Kmac256::new_customized(&KEY, S);
+    mac.update(&hex!("00010203"));
+    assert_eq!(
+        mac.finalize().into_bytes()[..],
+        hex!(
+            "
+            20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7
+            f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd
+        "
+        )[..]
+    );
+
+    let mut mac = Kmac256::new_varkey(&KEY).unwrap();
+    mac.update(&msg200());
+    assert_eq!(
+        mac.finalize().into_bytes()[..],
+        hex!(
+            "
+            75358cf39e41494e949707927cee0af20a3ff553904c86b08f21cc414bcfd691
+            589d27cf5e15369cbbff8b9a4c2eb17800855d0235ff635da82533ec6b759b69
+        "
+        )[..]
+    );
+
+    let mut mac = Kmac256::new_customized(&KEY, S);
+    mac.update(&msg200());
+    assert_eq!(
+        mac.finalize().into_bytes()[..],
+        hex!(
+            "
+            b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d9
+            70fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965
+        "
+        )[..]
+    );
+}
+
+#[test]
+fn kmacxof128_nist_samples() {
+    let mut res = [0u8; 32];
+
+    let mut mac = Kmac128::new_varkey(&KEY).unwrap();
+    mac.update(&hex!("00010203"));
+    mac.finalize_xof().read(&mut res);
+    assert_eq!(
+        res,
+        hex!("cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35")
+    );
+
+    let mut mac = Kmac128::new_customized(&KEY, S);
+    mac.update(&hex!("00010203"));
+    mac.finalize_xof().read(&mut res);
+    assert_eq!(
+        res,
+        hex!("31a44527b4ed9f5c6101d11de6d26f0620aa5c341def41299657fe9df1a3b16c")
+    );
+
+    let mut mac = Kmac128::new_customized(&KEY, S);
+    mac.update(&msg200());
+    mac.finalize_xof().read(&mut res);
+    assert_eq!(
+        res,
+        hex!("47026c7cd793084aa0283c253ef658490c0db61438b8326fe9bddf281b83ae0f")
+    );
+}
+
+#[test]
+fn kmacxof256_nist_samples() {
+    let mut res = [0u8; 64];
+
+    let mut mac = Kmac256::new_customized(&KEY, S);
+    mac.update(&hex!("00010203"));
+    mac.finalize_xof().read(&mut res);
+    assert_eq!(
+        res[..],
+        hex!(
+            "
+            1755133f1534752aad0748f2c706fb5c784512cab835cd15676b16c0c6647fa9
+            6faa7af634a0bf8ff6df39374fa00fad9a39e322a7c92065a64eb1fb0801eb2b
+        "
+        )[..]
+    );
+
+    let mut mac = Kmac256::new_varkey(&KEY).unwrap();
+    mac.update(&msg200());
+    mac.finalize_xof().read(&mut res);
+    assert_eq!(
+        res[..],
+        hex!(
+            "
+            ff7b171f1e8a2b24683eed37830ee797538ba8dc563f6da1e667391a75edc02c
+            a633079f81ce12a25f45615ec89972031d18337331d24ceb8f8ca8e6a19fd98b
+        "
+        )[..]
+    );
+
+    let mut mac = Kmac256::new_customized(&KEY, S);
+    mac.update(&msg200());
+    mac.finalize_xof().read(&mut res);
+    assert_eq!(
+        res[..],
+        hex!(
+            "
+            d5be731c954ed7732846bb59dbe3a8e30f83e77a4bff4459f2f1c2b4ecebb8ce
+            67ba01c62e8ab8578d2d499bd1bb276768781190020a306a97de281dcc30305d
+        "
+        )[..]
+    );
+}
+
+#[test]
+fn kmac128_custom_len() {
+    let mut mac = Kmac128::new_varkey(b"my key").unwrap();
+    mac.update(b"hello");
+    let mut res = [0u8; 13];
+    mac.finalize_into(&mut res);
+    assert_eq!(res, hex!("f46603d45ed6c68472bccd0aa4"));
+}
+
+#[test]
+fn kmac128_reset_and_verify() {
+    let mut mac = Kmac128::new_varkey(&KEY).unwrap();
+    mac.update(b"garbage");
+    mac.reset();
+    mac.update(&hex!("00010203"));
+    mac.verify(&hex!(
+        "e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e"
+    ))
+    .unwrap();
+}
@@ -22,3 +22,5 @@ new_test!(sha3_512, "sha3_512", sha3::Sha3_512, digest_test);
 
 new_test!(shake128, "shake128", sha3::Shake128, xof_test);
 new_test!(shake256, "shake256", sha3::Shake256, xof_test);
+// the `shake256` test vectors truncated to 24 bytes, SP 800-208 has no vectors of its own
+new_test!(shake256_192, "shake256_192", sha3::Shake256_192, digest_test);
@@ -0,0 +1,62 @@
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Shake128, Shake256};
+
+fn expected_output<D: Default + Update + ExtendableOutput>(msg: &[u8], len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len];
+    D::default().chain(msg).finalize_xof().read(&mut buf);
+    buf
+}
+
+#[test]
+fn seek_matches_sequential_read() {
+    let expected = expected_output::<Shake128>(b"seekable", 1000);
+    let mut reader = Shake128::default().chain(b"seekable").finalize_xof();
+
+    for &(start, len) in &[(0, 10), (167, 2), (168, 168), (500, 300), (5, 50), (336, 1)] {
+        reader.seek(start as u64);
+        assert_eq!(reader.position(), start as u64);
+        let mut buf = vec![0u8; len];
+        reader.read(&mut buf);
+        assert_eq!(buf[..], expected[start..start + len]);
+        assert_eq!(reader.position(), (start + len) as u64);
+    }
+}
+
+#[test]
+fn skip_matches_sequential_read() {
+    let expected = expected_output::<Shake256>(b"abc", 700);
+    let mut reader = Shake256::default().chain(b"abc").finalize_xof();
+
+    let mut buf = [0u8; 20];
+    reader.read(&mut buf[..3]);
+    reader.skip(133);
+    reader.read(&mut buf);
+    assert_eq!(buf[..], expected[136..156]);
+    reader.skip(500);
+    reader.read(&mut buf);
+    assert_eq!(buf[..], expected[656..676]);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn io_seek() {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let expected = expected_output::<Shake128>(b"seekable", 600);
+    let mut reader = Shake128::default().chain(b"seekable").finalize_xof();
+    let mut buf = [0u8; 16];
+
+    assert_eq!(Seek::seek(&mut reader, SeekFrom::Start(400)).unwrap(), 400);
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf[..], expected[400..416]);
+
+    assert_eq!(
+        Seek::seek(&mut reader, SeekFrom::Current(-300)).unwrap(),
+        116
+    );
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf[..], expected[116..132]);
+
+    assert!(Seek::seek(&mut reader, SeekFrom::Current(-200)).is_err());
+    assert!(Seek::seek(&mut reader, SeekFrom::End(0)).is_err());
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "rand_core")]
+
+use hex_literal::hex;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::rand_core::{RngCore, SeedableRng};
+use sha3::{Shake128, Shake128Rng, Shake256Rng};
+
+#[test]
+fn shake_rng_output() {
+    let mut buf = [0u8; 48];
+    Shake128Rng::new(b"seed").fill_bytes(&mut buf);
+    assert_eq!(
+        buf[..],
+        hex!(
+            "
+            94f06af98d047180fda011870e2c37eab192cdbc1a5b291f8a9db5ff75a18db4
+            9aa6d9f344c9b914ab6866ff5c0039dd
+        "
+        )[..]
+    );
+
+    let mut rng = Shake256Rng::new(b"seed");
+    assert_eq!(rng.next_u64(), 0xe69a1b1a4fbe5b35);
+    assert_eq!(rng.next_u32(), 0x7ebd625f);
+    rng.fill_bytes(&mut buf[..4]);
+    assert_eq!(buf[..4], hex!("13f24041"));
+}
+
+#[test]
+fn shake_rng_from_reader() {
+    let mut expected = [0u8; 100];
+    let mut hasher = Shake128::default();
+    hasher.update(b"expand me");
+    let reader = hasher.finalize_xof();
+    reader.clone().read(&mut expected);
+
+    let mut rng = Shake128Rng::from_reader(reader);
+    let mut buf = [0u8; 100];
+    for chunk in buf.chunks_mut(7) {
+        rng.fill_bytes(chunk);
+    }
+    assert_eq!(buf[..], expected[..]);
+}
+
+#[test]
+fn shake_rng_reseed_and_fork() {
+    let mut a = Shake256Rng::from_seed([42; 32]);
+    let mut b = a.clone();
+    let mut c = a.clone();
+    a.reseed(b"entropy");
+    b.reseed(b"entropy");
+    c.reseed(b"other entropy");
+    let x = a.next_u64();
+    assert_eq!(x, b.next_u64());
+    assert_ne!(x, c.next_u64());
+
+    let mut a = Shake256Rng::from_seed([42; 32]);
+    let mut b = a.clone();
+    let mut fa = a.fork(b"child");
+    let mut fb = b.fork(b"child");
+    let mut fc = b.fork(b"child");
+    let mut fd = a.fork(b"other");
+    let x = fa.next_u64();
+    assert_eq!(x, fb.next_u64());
+    assert_ne!(x, fc.next_u64());
+    assert_ne!(x, fd.next_u64());
+    assert_ne!(x, a.next_u64());
+}
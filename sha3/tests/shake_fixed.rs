@@ -0,0 +1,47 @@
+use hex_literal::hex;
+use sha3::digest::consts::U20;
+use sha3::{Digest, Shake128_256, Shake256Fixed, Shake256_512, Shake256_912};
+
+#[test]
+fn shake_fixed() {
+    assert_eq!(
+        Shake128_256::digest(b"abc")[..],
+        hex!("5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8")[..]
+    );
+    assert_eq!(
+        Shake256_512::digest(b"abc")[..],
+        hex!(
+            "
+            483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739
+            d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4
+        "
+        )[..]
+    );
+    assert_eq!(
+        Shake256_912::digest(b"")[..],
+        hex!(
+            "
+            46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f
+            d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be
+            141e96616fb13957692cc7edd0b45ae3dc07223c8e92937bef84bc0eab862853
+            349ec75546f58fb7c2775c38462c5010d846
+        "
+        )[..]
+    );
+}
+
+#[test]
+fn shake_fixed_reset() {
+    let mut hasher = Shake256Fixed::<U20>::new();
+    hasher.update(b"garbage");
+    hasher.reset();
+    hasher.update(b"a");
+    hasher.update(b"bc");
+    let res = hasher.finalize_reset();
+    assert_eq!(
+        res[..],
+        hex!("483366601360a8771c6863080cc4114d8db44530")[..]
+    );
+    hasher.update(b"abc");
+    assert_eq!(hasher.finalize(), res);
+}
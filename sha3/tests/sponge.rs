@@ -0,0 +1,107 @@
+use hex_literal::hex;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Keccak256, KeccakSponge, Sha3_512, Shake128};
+
+fn msg500() -> [u8; 500] {
+    let mut buf = [0u8; 500];
+    for (i, b) in buf.iter_mut().enumerate() {
+        *b = (i % 250) as u8;
+    }
+    buf
+}
+
+#[test]
+fn sponge_matches_fixed_hashes() {
+    let msg = msg500();
+
+    let mut sponge = KeccakSponge::new(72);
+    assert_eq!(sponge.capacity(), 128);
+    sponge.absorb(&msg);
+    sponge.pad(0x06);
+    let mut res = [0u8; 64];
+    sponge.squeeze(&mut res);
+    assert_eq!(res[..], Sha3_512::digest(&msg)[..]);
+
+    let mut sponge = KeccakSponge::new(136);
+    for chunk in msg.chunks(17) {
+        sponge.absorb(chunk);
+    }
+    sponge.pad(0x01);
+    let mut res = [0u8; 32];
+    sponge.squeeze(&mut res);
+    assert_eq!(res[..], Keccak256::digest(&msg)[..]);
+}
+
+#[test]
+fn sponge_matches_shake() {
+    let msg = msg500();
+    let mut expected = [0u8; 1000];
+    Shake128::default()
+        .chain(&msg[..])
+        .finalize_xof()
+        .read(&mut expected);
+
+    let mut sponge = KeccakSponge::new(168);
+    sponge.absorb(&msg);
+    sponge.pad(0x1F);
+    let mut res = [0u8; 1000];
+    for chunk in res.chunks_mut(33) {
+        sponge.squeeze(chunk);
+    }
+    assert_eq!(res[..], expected[..]);
+}
+
+#[test]
+fn sponge_unaligned_rate() {
+    // rate used by STROBE-128
+    let mut sponge = KeccakSponge::new(166);
+    sponge.absorb(&msg500());
+    sponge.pad(0x1F);
+
+    let mut res = [0u8; 364];
+    sponge.squeeze(&mut res);
+    assert_eq!(
+        res[..32],
+        hex!("cb7ecd56cf511f42dca2677db8c05e459bb40f66543fb41b3064ffb6cc0d3a41")
+    );
+    assert_eq!(
+        res[332..],
+        hex!("14cf56bc4b116013dc5bf4a7c5f2ab53f16a5eb88b0fe24c7a2e91f4668badc7")
+    );
+}
+
+#[test]
+fn sponge_duplex_and_ratchet() {
+    let mut a = KeccakSponge::new(166);
+    let mut b = a.clone();
+
+    let (mut out_a, mut out_b) = ([0u8; 32], [0u8; 32]);
+    a.duplex(b"hello", 0x1F, &mut out_a);
+    b.absorb(b"hello");
+    b.pad(0x1F);
+    b.squeeze(&mut out_b);
+    assert_eq!(out_a, out_b);
+
+    a.ratchet();
+    b.ratchet();
+
+    // squeezing right after the ratchet reads the zeroed outer part of the state
+    let mut outer = [0xFFu8; 166];
+    a.clone().squeeze(&mut outer);
+    assert!(outer.iter().all(|&v| v == 0));
+
+    a.duplex(b"", 0x1F, &mut out_a);
+    b.duplex(b"", 0x1F, &mut out_b);
+    assert_eq!(out_a, out_b);
+
+    let mut c = KeccakSponge::new(166);
+    c.duplex(b"hello", 0x1F, &mut out_b);
+    c.duplex(b"", 0x1F, &mut out_b);
+    assert_ne!(out_a, out_b);
+}
+
+#[test]
+#[should_panic]
+fn sponge_invalid_rate() {
+    KeccakSponge::new(200);
+}
@@ -0,0 +1,257 @@
+use core::num::NonZeroUsize;
+use hex_literal::hex;
+use sha3::digest::{ExtendableOutput, Reset, Update, XofReader};
+use sha3::{
+    ParallelHash128, ParallelHash256, ParallelHashXof128, ParallelHashXof256, TupleHash128,
+    TupleHash256, TupleHashXof128, TupleHashXof256,
+};
+
+fn block_size(n: usize) -> NonZeroUsize {
+    NonZeroUsize::new(n).unwrap()
+}
+
+// Samples from NIST SP 800-185:
+// https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+const TUPLE: [&[u8]; 3] = [
+    &hex!("000102"),
+    &hex!("101112131415"),
+    &hex!("202122232425262728"),
+];
+const TUPLE_S: &[u8] = b"My Tuple App";
+
+const PARALLEL_X: [u8; 24] = hex!("000102030405060710111213141516172021222324252627");
+const PARALLEL_S: &[u8] = b"Parallel Data";
+
+#[test]
+fn tuple_hash128_nist_samples() {
+    let mut res = [0u8; 32];
+
+    let mut h = TupleHash128::new(b"");
+    TUPLE[..2].iter().for_each(|e| h.update_element(e));
+    h.finalize_into(&mut res);
+    assert_eq!(
+        res,
+        hex!("c5d8786c1afb9b82111ab34b65b2c0048fa64e6d48e263264ce1707d3ffc8ed1")
+    );
+
+    let mut h = TupleHash128::new(TUPLE_S);
+    TUPLE[..2].iter().for_each(|e| h.update_element(e));
+    h.finalize_into(&mut res);
+    assert_eq!(
+        res,
+        hex!("75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb")
+    );
+
+    let mut h = TupleHash128::new(TUPLE_S);
+    TUPLE.iter().for_each(|e| h.update_element(e));
+    h.finalize_into(&mut res);
+    assert_eq!(
+        res,
+        hex!("e60f202c89a2631eda8d4c588ca5fd07f39e5151998deccf973adb3804bb6e84")
+    );
+}
+
+#[test]
+fn tuple_hash256_nist_samples() {
+    let mut res = [0u8; 64];
+
+    let mut h = TupleHash256::new(b"");
+    TUPLE[..2].iter().for_each(|e| h.update_element(e));
+    h.finalize_into(&mut res);
+    assert_eq!(
+        res[..],
+        hex!(
+            "
+            cfb7058caca5e668f81a12a20a2195ce97a925f1dba3e7449a56f82201ec6073
+            11ac2696b1ab5ea2352df1423bde7bd4bb78c9aed1a853c78672f9eb23bbe194
+        "
+        )[..]
+    );
+
+    let mut h = TupleHash256::new(TUPLE_S);
+    TUPLE.iter().for_each(|e| h.update_element(e));
+    h.finalize_into(&mut res);
+    assert_eq!(
+        res[..],
+        hex!(
+            "
+            45000be63f9b6bfd89f54717670f69a9bc763591a4f05c50d68891a744bcc6e7
+            d6d5b5e82c018da999ed35b0bb49c9678e526abd8e85c13ed254021db9e790ce
+        "
+        )[..]
+    );
+}
+
+#[test]
+fn tuple_hash_xof_nist_samples() {
+    let mut res = [0u8; 32];
+    let mut h = TupleHashXof128::new(TUPLE_S);
+    TUPLE.iter().for_each(|e| h.update_element(e));
+    h.finalize_xof().read(&mut res);
+    assert_eq!(
+        res,
+        hex!("d66775b8d49e28788c809ccf9a22cf5f176ee485d0c22bc48f56790679a5222f")
+    );
+
+    let mut res = [0u8; 64];
+    let mut h = TupleHashXof256::new(b"");
+    TUPLE[..2].iter().for_each(|e| h.update_element(e));
+    h.finalize_xof().read(&mut res);
+    assert_eq!(
+        res[..],
+        hex!(
+            "
+            f116a4d7bb417f5894296d235e35081a412631bc131720f9815f6e07cf5a009f
+            a5bbb41115fd1cfb4b6778d1add40433ebd6b9f22839db86fb8f7ed26da2bb4c
+        "
+        )[..]
+    );
+}
+
+#[test]
+fn tuple_hash_is_unambiguous() {
+    let mut a = [0u8; 32];
+    let mut h = TupleHash128::new(b"");
+    h.update_element(b"ab");
+    h.update_element(b"c");
+    h.finalize_into(&mut a);
+
+    let mut b = [0u8; 32];
+    let mut h = TupleHash128::new(b"");
+    h.update_element(b"a");
+    h.update_element(b"bc");
+    h.finalize_into(&mut b);
+
+    assert_ne!(a, b);
+}
+
+#[test]
+fn parallel_hash128_nist_samples() {
+    let mut res = [0u8; 32];
+
+    let mut h = ParallelHash128::new(b"", block_size(8));
+    h.update(&PARALLEL_X[..]);
+    h.finalize_into(&mut res);
+    assert_eq!(
+        res,
+        hex!("ba8dc1d1d979331d3f813603c67f72609ab5e44b94a0b8f9af46514454a2b4f5")
+    );
+
+    let mut h = ParallelHash128::new(PARALLEL_S, block_size(8));
+    h.update(&PARALLEL_X[..]);
+    h.finalize_into(&mut res);
+    assert_eq!(
+        res,
+        hex!("fc484dcb3f84dceedc353438151bee58157d6efed0445a81f165e495795b7206")
+    );
+}
+
+#[test]
+fn parallel_hash256_nist_samples() {
+    let mut res = [0u8; 64];
+
+    let mut h = ParallelHash256::new(b"", block_size(8));
+    h.update(&PARALLEL_X[..]);
+    h.finalize_into(&mut res);
+    assert_eq!(
+        res[..],
+        hex!(
+            "
+            bc1ef124da34495e948ead207dd9842235da432d2bbc54b4c110e64c45110553
+            1b7f2a3e0ce055c02805e7c2de1fb746af97a1dd01f43b824e31b87612410429
+        "
+        )[..]
+    );
+
+    let mut h = ParallelHash256::new(PARALLEL_S, block_size(8));
+    h.update(&PARALLEL_X[..]);
+    h.finalize_into(&mut res);
+    assert_eq!(
+        res[..],
+        hex!(
+            "
+            cdf15289b54f6212b4bc270528b49526006dd9b54e2b6add1ef6900dda3963bb
+            33a72491f236969ca8afaea29c682d47a393c065b38e29fae651a2091c833110
+        "
+        )[..]
+    );
+}
+
+#[test]
+fn parallel_hash_xof_nist_samples() {
+    let mut res = [0u8; 32];
+    let mut h = ParallelHashXof128::new(PARALLEL_S, block_size(8));
+    h.update(&PARALLEL_X[..]);
+    h.finalize_xof().read(&mut res);
+    assert_eq!(
+        res,
+        hex!("9c9461abd5f9c9f7ab8d14f53209898bb9c9cc5e906c9092a5b9565d9977e69a")
+    );
+
+    let mut res = [0u8; 64];
+    let mut h = ParallelHashXof256::new(b"", block_size(8));
+    h.update(&PARALLEL_X[..]);
+    h.finalize_xof().read(&mut res);
+    assert_eq!(
+        res[..],
+        hex!(
+            "
+            4ea4130473a29046931724a525b134edde7dec45c6409769cd2a689bf17be578
+            c7c28086125398bd79870a23e5c1a6b68bee23a9568ce80077a05256389306c3
+        "
+        )[..]
+    );
+}
+
+#[test]
+fn parallel_hash_partial_leaf() {
+    let mut msg = [0u8; 1000];
+    for (i, b) in msg.iter_mut().enumerate() {
+        *b = (i % 251) as u8;
+    }
+    let expected = hex!(
+        "
+        daa75ef5b71173f246e7f6d146e10c7ad2d4258aec06d4b7ccc6a68d63e5810a
+        63977c84bde5356f151a97c372eb00865af4
+    "
+    );
+
+    let mut h = ParallelHash128::new(b"xyz", block_size(64));
+    h.update(&msg[..]);
+    let mut res = [0u8; 50];
+    h.finalize_into(&mut res);
+    assert_eq!(res[..], expected[..]);
+
+    // result must not depend on how the input is split into updates
+    let mut h = ParallelHash128::new(b"xyz", block_size(64));
+    h.update(b"garbage");
+    h.reset();
+    for chunk in msg.chunks(37) {
+        h.update(chunk);
+    }
+    let mut res = [0u8; 50];
+    h.finalize_into(&mut res);
+    assert_eq!(res[..], expected[..]);
+}
+
+/// Large updates are hashed on multiple threads with the `rayon` feature,
+/// small ones never are.
+#[test]
+fn parallel_hash_large_input() {
+    let msg: Vec<u8> = (0..300_000).map(|i| (i % 251) as u8).collect();
+    for &b in [1000, 8192].iter() {
+        let mut h = ParallelHash256::new(b"xyz", block_size(b));
+        h.update(&msg[..5]);
+        h.update(&msg[5..]);
+        let mut expected = [0u8; 64];
+        h.finalize_into(&mut expected);
+
+        let mut h = ParallelHash256::new(b"xyz", block_size(b));
+        for chunk in msg.chunks(37) {
+            h.update(chunk);
+        }
+        let mut res = [0u8; 64];
+        h.finalize_into(&mut res);
+        assert_eq!(res, expected);
+    }
+}
@@ -0,0 +1,86 @@
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Keccak256Full, Sha3_256, Sha3_512, Shake128, Shake256};
+
+fn messages() -> Vec<Vec<u8>> {
+    // lengths around the rate boundaries of all SHA-3 variants
+    [0, 1, 71, 72, 73, 135, 136, 137, 168, 300, 1000, 5, 2000]
+        .iter()
+        .map(|&n| (0..n).map(|i| (i * 7 + n) as u8).collect())
+        .collect()
+}
+
+#[test]
+fn digest_x4_matches_digest() {
+    let msgs = messages();
+    let inputs = [&msgs[0][..], &msgs[10][..], &msgs[6][..], &msgs[3][..]];
+    let res = Sha3_256::digest_x4(inputs);
+    for (r, m) in res.iter().zip(inputs.iter()) {
+        assert_eq!(*r, Sha3_256::digest(m));
+    }
+    let res = Sha3_512::digest_x4(inputs);
+    for (r, m) in res.iter().zip(inputs.iter()) {
+        assert_eq!(*r, Sha3_512::digest(m));
+    }
+    let res = Keccak256Full::digest_x4(inputs);
+    for (r, m) in res.iter().zip(inputs.iter()) {
+        assert_eq!(*r, Keccak256Full::digest(m));
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn digest_many_matches_digest() {
+    let msgs = messages();
+    let inputs: Vec<&[u8]> = msgs.iter().map(|m| &m[..]).collect();
+    let res = Sha3_512::digest_many(&inputs);
+    assert_eq!(res.len(), inputs.len());
+    for (r, m) in res.iter().zip(inputs.iter()) {
+        assert_eq!(*r, Sha3_512::digest(m));
+    }
+    assert!(Sha3_512::digest_many(&[]).is_empty());
+}
+
+fn shake_output<D: Default + Update + ExtendableOutput>(msg: &[u8], len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len];
+    D::default().chain(msg).finalize_xof().read(&mut buf);
+    buf
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn digest_xof_many_matches_shake() {
+    let msgs = messages();
+    let inputs: Vec<&[u8]> = msgs.iter().map(|m| &m[..]).collect();
+
+    let readers = Shake256::digest_xof_many(&inputs);
+    assert_eq!(readers.len(), inputs.len());
+    for (mut reader, m) in readers.into_iter().zip(inputs.iter()) {
+        let mut buf = [0u8; 300];
+        reader.read(&mut buf);
+        assert_eq!(buf[..], shake_output::<Shake256>(m, 300)[..]);
+    }
+}
+
+#[test]
+fn digest_xof_x4_matches_shake() {
+    let msgs = messages();
+    let inputs = [&msgs[1][..], &msgs[8][..], &msgs[9][..], &msgs[12][..]];
+    for (mut reader, m) in Shake128::digest_xof_x4(inputs)
+        .iter()
+        .cloned()
+        .zip(inputs.iter())
+    {
+        let mut buf = [0u8; 64];
+        reader.read(&mut buf);
+        assert_eq!(buf[..], shake_output::<Shake128>(m, 64)[..]);
+    }
+    for (mut reader, m) in Shake256::digest_xof_x4(inputs)
+        .iter()
+        .cloned()
+        .zip(inputs.iter())
+    {
+        let mut buf = [0u8; 200];
+        reader.read(&mut buf);
+        assert_eq!(buf[..], shake_output::<Shake256>(m, 200)[..]);
+    }
+}